@@ -1,8 +1,10 @@
-use AIWeatherRouting::engine::router::IsochroneRouter;
-use AIWeatherRouting::engine::models::{Coordinate, WindData, CurrentData, BoatState};
+use AIWeatherRouting::engine::router::{Conditions, IsochroneRouter};
+use AIWeatherRouting::engine::models::{Coordinate, WindData, CurrentData, BoatState, RouteLeg};
 use AIWeatherRouting::engine::physics::PhysicsModel;
 use AIWeatherRouting::parsers::polars::PolarData;
 use AIWeatherRouting::engine::mask::LandMask;
+use AIWeatherRouting::engine::models::{CurrentField, WaveField};
+use AIWeatherRouting::parsers::grib::GribLoader;
 use std::time::Instant;
 
 fn main() {
@@ -15,48 +17,111 @@ fn main() {
     let time_step = 3600.0; // 1 hour
 
     println!("Loading Polar...");
-    let polar = PolarData::load_from_csv("data/imoca_60.csv");
+    // Optional polar file (any supported format) as third argument, then
+    // per-sail polars, then the single envelope table
+    let polar = if let Some(path) = std::env::args().nth(3) {
+        PolarData::load(&path)
+    } else if std::path::Path::new("data/sails").is_dir() {
+        PolarData::load_sail_set("data/sails")
+    } else {
+        PolarData::load_from_csv("data/imoca_60.csv")
+    };
+    let polar = match polar {
+        Ok(polar) => polar,
+        Err(e) => {
+            println!("Failed to load polar: {}", e);
+            std::process::exit(1);
+        }
+    };
     println!("Polar loaded: {} TWA, {} TWS points", polar.twa.len(), polar.tws.len());
+    if !polar.sails.is_empty() {
+        println!("Sails: {}", polar.sails.join(", "));
+        println!("Crossover chart (TWA \\ TWS {:?}):", polar.tws);
+        for (twa, row) in polar.twa.iter().zip(polar.crossover_chart()) {
+            let names: Vec<&str> = row.iter().map(|s| s.map(|s| polar.sails[s].as_str()).unwrap_or("-")).collect();
+            println!("  {:>5.0}°  {}", twa, names.join("\t"));
+        }
+    }
 
     let physics = PhysicsModel::new();
     let land_mask = LandMask::new(); // Empty mask for simplicity
 
+    // Optional surface current GRIB as first argument
+    let current_field = match std::env::args().nth(1) {
+        Some(path) => {
+            println!("Loading currents from {}...", path);
+            match GribLoader::new().load_current_data(&path) {
+                Ok(field) => {
+                    println!("Currents loaded: {} forecast steps", field.snapshots.len());
+                    field
+                }
+                Err(e) => {
+                    println!("Failed to load currents ({}), routing without them", e);
+                    CurrentField::default()
+                }
+            }
+        }
+        None => CurrentField::default(),
+    };
+
+    // Optional wave GRIB as second argument
+    let wave_field = match std::env::args().nth(2) {
+        Some(path) => {
+            println!("Loading waves from {}...", path);
+            match GribLoader::new().load_wave_data(&path) {
+                Ok(field) => {
+                    println!("Waves loaded: {} forecast steps", field.snapshots.len());
+                    field
+                }
+                Err(e) => {
+                    println!("Failed to load waves ({}), routing without them", e);
+                    WaveField::default()
+                }
+            }
+        }
+        None => WaveField::default(),
+    };
+
     let mut router = IsochroneRouter::new(start, destination, time_step);
     
-    let initial_state = BoatState {
-        position: start,
-        time: chrono::Utc::now(),
-        elapsed_time: 0.0,
-    };
+    let initial_state = BoatState::new(start, chrono::Utc::now());
 
-    let mut current_front = vec![initial_state];
+    // 2. Solve
+    // Simple uniform wind: 20 knots from North (TWD 0)
+    // 20 knots = 10.288 m/s
+    // Wind FROM North => u=0, v=-10.288
+    let wind_data = WindData { u: 0.0, v: -10.288 };
 
-    // 2. Run Steps
-    for step in 1..=5 {
-        let start_time = Instant::now();
-        println!("\n--- Step {} ---", step);
-        
-        // Simple uniform wind: 20 knots from North (TWD 0)
-        // 20 knots = 10.288 m/s
-        // Wind FROM North => u=0, v=-10.288
-        let wind_data = WindData { u: 0.0, v: -10.288 };
+    let start_time = Instant::now();
+    let result = router.solve(
+        initial_state,
+        &physics,
+        &polar,
+        &land_mask,
+        &Conditions::new(
+            |_, _| Some(wind_data),
+            |coord, time| current_field.current_at(coord, time).unwrap_or(CurrentData { u: 0.0, v: 0.0 }),
+            |coord, time| wave_field.sea_state_at(coord, time)
+        )
+    );
+    let duration = start_time.elapsed();
 
-        current_front = router.step(
-            &current_front,
-            &physics,
-            &polar,
-            &land_mask,
-            |_| wind_data,
-            |_| CurrentData { u: 0.0, v: 0.0 }
-        );
+    println!("\nOutcome: {:?}", result.outcome);
+    println!("Steps: {}", result.fronts.len() - 1);
+    println!("Calculation time: {:?}", duration);
 
-        let duration = start_time.elapsed();
-        println!("Front points: {}", current_front.len());
-        println!("Calculation time: {:?}", duration);
+    println!("\n--- Best Route ---");
+    for (i, leg) in result.route.iter().enumerate() {
+        println!("Leg {}: {} -> Lat: {:.4}, Lon: {:.4}, HDG: {:.0}°, {}, SOG: {:.1} kt",
+            i + 1, leg.start_time.format("%H:%M"), leg.to.lat, leg.to.lon,
+            leg.heading, leg.annotation(), leg.sog * 1.94384);
+    }
 
-        if let Some(first) = current_front.first() {
-            println!("First point: Lat: {:.4}, Lon: {:.4}, Elapsed: {}h", 
-                first.position.lat, first.position.lon, first.elapsed_time / 3600.0);
+    let sail_changes = RouteLeg::sail_changes(&result.route);
+    if !sail_changes.is_empty() {
+        println!("\n--- Sail Plan ---");
+        for (time, sail) in sail_changes {
+            println!("{} UTC: hoist {}", time.format("%Y-%m-%d %H:%M"), polar.sails[sail]);
         }
     }
 
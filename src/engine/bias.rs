@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+use log::info;
+use bevy::prelude::Resource;
+
+use crate::engine::models::{Coordinate, WindData, WindField, WindObservation};
+
+/// Forecast winds below this speed (m/s) are ignored for the direction bias
+const MIN_DIRECTION_SPEED: f32 = 2.0;
+
+/// Wind bias measured in one region: observed vs forecast
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RegionBias {
+    /// Mean position of the observations
+    pub center: Coordinate,
+    /// Observed / forecast wind speed (1.1 = forecast 10% too light)
+    pub speed_factor: f32,
+    /// Observed - forecast wind direction, degrees clockwise
+    pub direction_offset: f32,
+    /// Number of observations compared
+    pub count: usize,
+}
+
+/// Per-region forecast bias computed from point observations, with a spatially
+/// blended correction that fades back to no correction away from the observations.
+#[derive(Debug, Clone, Default, Resource)]
+pub struct BiasModel {
+    /// Region size in degrees, also used as the blending length scale
+    pub region_size: f64,
+    pub regions: Vec<RegionBias>,
+    /// Weight of the "no correction" prior, in number of observations
+    pub prior_weight: f32,
+}
+
+#[derive(Default)]
+struct RegionAccumulator {
+    lat_sum: f64,
+    lon_sum: f64,
+    observed_speed: f32,
+    forecast_speed: f32,
+    dir_x: f32,
+    dir_y: f32,
+    count: usize,
+}
+
+impl BiasModel {
+    /// Compares each observation with the forecast valid at the same time and place,
+    /// grouped into `region_size` x `region_size` degree regions. Regions with fewer
+    /// than `min_count` observations are dropped.
+    pub fn compute(wind_field: &WindField, observations: &[WindObservation], region_size: f64, min_count: usize) -> Self {
+        let mut accumulators: HashMap<(i32, i32), RegionAccumulator> = HashMap::new();
+
+        for obs in observations {
+            let Some(forecast) = wind_field.wind_at(&obs.position, obs.time) else { continue };
+            let key = (
+                (obs.position.lon / region_size).floor() as i32,
+                (obs.position.lat / region_size).floor() as i32,
+            );
+            let acc = accumulators.entry(key).or_default();
+
+            acc.lat_sum += obs.position.lat;
+            acc.lon_sum += obs.position.lon;
+            acc.observed_speed += obs.wind.speed();
+            acc.forecast_speed += forecast.speed();
+            if forecast.speed() >= MIN_DIRECTION_SPEED && obs.wind.speed() >= MIN_DIRECTION_SPEED {
+                let diff = (obs.wind.direction() - forecast.direction()).to_radians();
+                acc.dir_x += diff.sin();
+                acc.dir_y += diff.cos();
+            }
+            acc.count += 1;
+        }
+
+        let mut regions: Vec<RegionBias> = accumulators.into_values()
+            .filter(|acc| acc.count >= min_count.max(1) && acc.forecast_speed > 0.0)
+            .map(|acc| RegionBias {
+                center: Coordinate::new(acc.lat_sum / acc.count as f64, acc.lon_sum / acc.count as f64),
+                speed_factor: acc.observed_speed / acc.forecast_speed,
+                direction_offset: if acc.dir_x == 0.0 && acc.dir_y == 0.0 { 0.0 } else { acc.dir_x.atan2(acc.dir_y).to_degrees() },
+                count: acc.count,
+            })
+            .collect();
+        regions.sort_by_key(|region| std::cmp::Reverse(region.count));
+
+        info!("Computed wind bias over {} regions", regions.len());
+        Self { region_size, regions, prior_weight: 1.0 }
+    }
+
+    /// Blended `(speed_factor, direction_offset)` at `coord`. Each region weighs by its
+    /// observation count and a Gaussian of the distance to its center.
+    pub fn correction_at(&self, coord: &Coordinate) -> (f32, f32) {
+        let mut weight_sum = self.prior_weight;
+        let mut factor_sum = self.prior_weight;
+        let mut dir_x = 0.0;
+        let mut dir_y = self.prior_weight;
+
+        for region in &self.regions {
+            let d_lat = region.center.lat - coord.lat;
+            let d_lon = (region.center.lon - coord.lon) * coord.lat.to_radians().cos();
+            let dist_sq = (d_lat * d_lat + d_lon * d_lon) / (self.region_size * self.region_size);
+            let weight = region.count as f32 * (-0.5 * dist_sq).exp() as f32;
+
+            weight_sum += weight;
+            factor_sum += weight * region.speed_factor;
+            dir_x += weight * region.direction_offset.to_radians().sin();
+            dir_y += weight * region.direction_offset.to_radians().cos();
+        }
+
+        (factor_sum / weight_sum, dir_x.atan2(dir_y).to_degrees())
+    }
+
+    /// Scales and rotates the wind vector by the blended correction at `coord`
+    pub fn correct(&self, coord: &Coordinate, wind: WindData) -> WindData {
+        let (factor, offset) = self.correction_at(coord);
+        // Rotating the direction clockwise rotates the vector clockwise too
+        let (sin, cos) = offset.to_radians().sin_cos();
+        WindData {
+            u: factor * (wind.u * cos + wind.v * sin),
+            v: factor * (-wind.u * sin + wind.v * cos),
+        }
+    }
+
+    /// Applies the correction to every grid point of every forecast step.
+    /// Does nothing when no region was measured.
+    pub fn apply(&self, wind_field: &mut WindField) {
+        if self.regions.is_empty() {
+            return;
+        }
+        for snapshot in &mut wind_field.snapshots {
+            let grid = snapshot.grid;
+            for idx in 0..snapshot.point_count() {
+                let coord = grid.coord_at(idx);
+                let corrected = self.correct(&coord, WindData { u: snapshot.u[idx], v: snapshot.v[idx] });
+                snapshot.u[idx] = corrected.u;
+                snapshot.v[idx] = corrected.v;
+            }
+        }
+        wind_field.bias_corrected = true;
+        info!("Applied wind bias correction to {} forecast steps", wind_field.snapshots.len());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::models::{GridGeometry, GridSnapshot, ObservationSource};
+
+    fn westerly_field() -> WindField {
+        // 10 m/s from the West over a 20x20 degree area
+        let grid = GridGeometry { lat0: 40.0, lon0: -20.0, dlat: 1.0, dlon: 1.0, ni: 21, nj: 21 };
+        let mut field = WindField::default();
+        field.insert_snapshot(GridSnapshot::new(chrono::Utc::now(), grid, vec![10.0; grid.len()], vec![0.0; grid.len()]));
+        field
+    }
+
+    #[test]
+    fn test_bias_compute_and_apply() {
+        let mut field = westerly_field();
+        let time = field.snapshots[0].valid_time;
+
+        // Observations 10% stronger and veered by 10 degrees around 45N 15W
+        let observations: Vec<WindObservation> = (0..5).map(|i| WindObservation {
+            source: ObservationSource::Buoy,
+            position: Coordinate::new(45.0 + i as f64 * 0.2, -15.0),
+            time,
+            wind: WindData::from_speed_direction(11.0, 280.0),
+        }).collect();
+
+        let bias = BiasModel::compute(&field, &observations, 5.0, 3);
+        assert_eq!(bias.regions.len(), 1);
+        let region = bias.regions[0];
+        assert_eq!(region.count, 5);
+        assert!((region.speed_factor - 1.1).abs() < 1e-3);
+        assert!((region.direction_offset - 10.0).abs() < 1e-2);
+
+        bias.apply(&mut field);
+        assert!(field.bias_corrected);
+
+        // Close to the observations the correction is almost fully applied
+        let near = field.wind_at(&Coordinate::new(45.0, -15.0), time).unwrap();
+        assert!(near.speed() > 10.8, "Corrected speed {}", near.speed());
+        assert!((near.direction() - 278.0).abs() < 2.5, "Corrected direction {}", near.direction());
+
+        // Far away the forecast is left almost untouched
+        let far = field.wind_at(&Coordinate::new(59.0, 0.0), time).unwrap();
+        assert!((far.speed() - 10.0).abs() < 0.05);
+        assert!((far.direction() - 270.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_bias_min_count() {
+        let mut field = westerly_field();
+        let observations = vec![WindObservation {
+            source: ObservationSource::Ship,
+            position: Coordinate::new(45.0, -15.0),
+            time: field.snapshots[0].valid_time,
+            wind: WindData::from_speed_direction(20.0, 270.0),
+        }];
+
+        let bias = BiasModel::compute(&field, &observations, 5.0, 3);
+        assert!(bias.regions.is_empty());
+        assert_eq!(bias.correction_at(&Coordinate::new(45.0, -15.0)), (1.0, 0.0));
+
+        // Nothing measured, nothing corrected
+        bias.apply(&mut field);
+        assert!(!field.bias_corrected);
+    }
+}
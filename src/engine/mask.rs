@@ -5,6 +5,8 @@ use log::info;
 
 pub const NX: u64 = 86400;
 pub const NY: u64 = 43200;
+/// Mask cells per degree of latitude or longitude
+pub const CELLS_PER_DEGREE: f64 = 240.0;
 
 #[derive(Resource)]
 pub struct LandMask {
@@ -39,19 +41,22 @@ impl LandMask {
     }
 
     fn coords_to_indices(&self, lon: f64, lat: f64) -> (u64, u64) {
-        // Affine transform: sa = 240, sc = 43200, se = 240, sf = 21600
-        let x = (lon * 240.0 + 43200.0) as u64;
-        let y = (lat * 240.0 + 21600.0) as u64;
-        (x.clamp(0, NX - 1), y.clamp(0, NY - 1))
+        // Affine transform: sa = 240, sc = 43200, se = 240, sf = 21600.
+        // Longitude wraps around the globe, latitude is clamped at the poles.
+        let x = ((Coordinate::normalize_lon(lon) * CELLS_PER_DEGREE + 43200.0).floor() as i64).rem_euclid(NX as i64) as u64;
+        let y = (lat * CELLS_PER_DEGREE + 21600.0).floor().clamp(0.0, (NY - 1) as f64) as u64;
+        (x, y)
     }
 
-    /// Adds a rectangular bounding box of coordinates to the roaring bitmap as Land (for tests)
+    /// Adds a rectangular bounding box of coordinates to the roaring bitmap as Land (for tests).
+    /// A box with `min_lon > max_lon` crosses the anti-meridian.
     pub fn add_land_box(&mut self, min_lon: f64, max_lon: f64, min_lat: f64, max_lat: f64) {
         let (min_x, min_y) = self.coords_to_indices(min_lon, min_lat);
         let (max_x, max_y) = self.coords_to_indices(max_lon, max_lat);
+        let columns: Vec<u64> = if min_x <= max_x { (min_x..=max_x).collect() } else { (min_x..NX).chain(0..=max_x).collect() };
 
         for y in min_y..=max_y {
-            for x in min_x..=max_x {
+            for &x in &columns {
                 self.mask.insert(y * NX + x);
             }
         }
@@ -60,9 +65,61 @@ impl LandMask {
     /// Checks if a coordinate is over land
     pub fn is_land(&self, coord: &Coordinate) -> bool {
         let (x, y) = self.coords_to_indices(coord.lon, coord.lat);
-        if y >= NY { return false; }
         self.mask.contains(y * NX + x)
     }
+
+    fn is_land_cell(&self, x: i64, y: i64) -> bool {
+        let x = x.rem_euclid(NX as i64) as u64;
+        let y = y.clamp(0, NY as i64 - 1) as u64;
+        self.mask.contains(y * NX + x)
+    }
+
+    /// Fraction of the straight lon/lat segment `from -> to` at which it first enters a land
+    /// cell, visiting every cell the segment crosses. `None` if it stays over water.
+    /// Only meant for short segments: longer legs should be split along their great circle.
+    pub fn first_land_along(&self, from: &Coordinate, to: &Coordinate) -> Option<f64> {
+        let to = to.unwrapped_around(from.lon);
+        let (x0, y0) = (from.lon * CELLS_PER_DEGREE + 43200.0, from.lat * CELLS_PER_DEGREE + 21600.0);
+        let (x1, y1) = (to.lon * CELLS_PER_DEGREE + 43200.0, to.lat * CELLS_PER_DEGREE + 21600.0);
+        let (dx, dy) = (x1 - x0, y1 - y0);
+
+        // Grid traversal: step into whichever neighbouring cell the segment reaches first
+        let (mut x, mut y) = (x0.floor() as i64, y0.floor() as i64);
+        let (end_x, end_y) = (x1.floor() as i64, y1.floor() as i64);
+        let axis = |origin: f64, cell: i64, delta: f64| -> (i64, f64, f64) {
+            if delta > 0.0 {
+                (1, ((cell + 1) as f64 - origin) / delta, 1.0 / delta)
+            } else if delta < 0.0 {
+                (-1, (cell as f64 - origin) / delta, -1.0 / delta)
+            } else {
+                (0, f64::INFINITY, f64::INFINITY)
+            }
+        };
+        let (step_x, mut next_x, delta_x) = axis(x0, x, dx);
+        let (step_y, mut next_y, delta_y) = axis(y0, y, dy);
+
+        let mut t = 0.0;
+        loop {
+            if self.is_land_cell(x, y) {
+                return Some(t);
+            }
+            if x == end_x && y == end_y {
+                return None;
+            }
+            if next_x < next_y {
+                x += step_x;
+                t = next_x;
+                next_x += delta_x;
+            } else {
+                y += step_y;
+                t = next_y;
+                next_y += delta_y;
+            }
+            if t > 1.0 {
+                return None;
+            }
+        }
+    }
 }
 #[cfg(test)]
 mod tests {
@@ -80,4 +137,40 @@ mod tests {
         let sea = Coordinate::new(40.0, -30.0);
         assert!(!mask.is_land(&sea), "Mid-Atlantic should be at sea");
     }
+
+    #[test]
+    fn test_land_mask_wraps_longitude() {
+        let mut mask = LandMask::new();
+        // Box across the anti-meridian, e.g. around Taveuni (Fiji)
+        mask.add_land_box(179.8, -179.8, -17.0, -16.8);
+
+        assert!(mask.is_land(&Coordinate::new(-16.9, 179.9)));
+        assert!(mask.is_land(&Coordinate::new(-16.9, -179.9)));
+        assert!(mask.is_land(&Coordinate::new(-16.9, 180.1)), "Longitudes past 180 wrap around");
+        assert!(!mask.is_land(&Coordinate::new(-16.9, 179.5)));
+        assert!(!mask.is_land(&Coordinate::new(-16.9, 0.0)), "The box must not span the globe");
+
+        // Latitudes beyond the poles clamp instead of wrapping into another row
+        mask.add_land_box(-10.0, 10.0, 89.9, 90.0);
+        assert!(mask.is_land(&Coordinate::new(91.0, 0.0)));
+    }
+
+    #[test]
+    fn test_first_land_along_segment() {
+        let mut mask = LandMask::new();
+        // Strip of land one cell wide along the meridian 0.5 E
+        mask.add_land_box(0.5, 0.5, 45.0, 46.0);
+
+        // Both ends are at sea but the segment crosses the strip halfway
+        let t = mask.first_land_along(&Coordinate::new(45.5, 0.4), &Coordinate::new(45.5, 0.6)).unwrap();
+        assert!((t - 0.5).abs() < 0.03, "Entered land at {}", t);
+        assert_eq!(mask.first_land_along(&Coordinate::new(45.5, 0.4), &Coordinate::new(45.6, 0.45)), None);
+        assert_eq!(mask.first_land_along(&Coordinate::new(44.0, 0.4), &Coordinate::new(44.5, 0.6)), None);
+        assert_eq!(mask.first_land_along(&Coordinate::new(45.5, 0.5), &Coordinate::new(45.5, 0.6)), Some(0.0));
+
+        // A segment across the anti-meridian goes the short way round
+        mask.add_land_box(179.9, -179.9, -1.0, 1.0);
+        assert!(mask.first_land_along(&Coordinate::new(0.0, 179.5), &Coordinate::new(0.0, -179.5)).is_some());
+        assert_eq!(mask.first_land_along(&Coordinate::new(0.0, 179.5), &Coordinate::new(0.0, 179.8)), None);
+    }
 }
@@ -2,3 +2,6 @@ pub mod models;
 pub mod router;
 pub mod physics;
 pub mod mask;
+pub mod projection;
+pub mod bias;
+pub mod polar_learning;
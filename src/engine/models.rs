@@ -11,6 +11,22 @@ impl Coordinate {
     pub fn new(lat: f64, lon: f64) -> Self {
         Self { lat, lon }
     }
+
+    /// Wraps a longitude into [-180, 180)
+    pub fn normalize_lon(lon: f64) -> f64 {
+        (lon + 180.0).rem_euclid(360.0) - 180.0
+    }
+
+    /// Same position with the longitude wrapped into [-180, 180)
+    pub fn normalized(self) -> Self {
+        Self { lat: self.lat, lon: Self::normalize_lon(self.lon) }
+    }
+
+    /// Same position with the longitude within 180 degrees of `reference_lon`, so that
+    /// nearby positions on both sides of the anti-meridian have continuous longitudes
+    pub fn unwrapped_around(self, reference_lon: f64) -> Self {
+        Self { lat: self.lat, lon: reference_lon + Self::normalize_lon(self.lon - reference_lon) }
+    }
 }
 
 /// Weather data for wind at a specific point
@@ -23,6 +39,15 @@ pub struct WindData {
 }
 
 impl WindData {
+    /// Builds the components from a speed (m/s) and the direction the wind comes from (degrees)
+    pub fn from_speed_direction(speed: f32, direction: f32) -> Self {
+        let dir_rad = direction.to_radians();
+        Self {
+            u: -speed * dir_rad.sin(),
+            v: -speed * dir_rad.cos(),
+        }
+    }
+
     pub fn speed(&self) -> f32 {
         (self.u.powi(2) + self.v.powi(2)).sqrt()
     }
@@ -36,68 +61,323 @@ impl WindData {
     }
 }
 
-use std::collections::HashMap;
+use chrono::{DateTime, Utc};
 
-/// Global resource to hold the loaded wind data for the frontend mapping
-#[derive(Resource, Default, Debug, Clone)]
-pub struct WindField {
-    /// 1x1 degree spatial chunks storing data points. 
-    /// Key: (lon.floor(), lat.floor())
-    pub chunks: HashMap<(i32, i32), Vec<(Coordinate, WindData)>>,
+/// Geometry of a regular lat/lon grid, as described by the GRIB message.
+/// Values are stored row by row: `index = j * ni + i`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GridGeometry {
+    /// Latitude of the first grid point (degrees)
+    pub lat0: f64,
+    /// Longitude of the first grid point (degrees, in [-180, 180))
+    pub lon0: f64,
+    /// Latitude increment between rows, negative when scanning North to South
+    pub dlat: f64,
+    /// Longitude increment between columns, negative when scanning East to West
+    pub dlon: f64,
+    /// Number of points along a parallel
+    pub ni: usize,
+    /// Number of points along a meridian
+    pub nj: usize,
 }
 
-impl WindField {
-    pub fn insert_point(&mut self, coord: Coordinate, wind: WindData) {
-        let chunk_x = coord.lon.floor() as i32;
-        let chunk_y = coord.lat.floor() as i32;
-        self.chunks.entry((chunk_x, chunk_y)).or_default().push((coord, wind));
+impl GridGeometry {
+    pub fn len(&self) -> usize {
+        self.ni * self.nj
     }
-    
-    pub fn get_bounds(&self) -> Option<(f64, f64, f64, f64)> {
-        if self.chunks.is_empty() {
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether the grid wraps around the globe in longitude
+    pub fn is_global(&self) -> bool {
+        (self.ni as f64 * self.dlon.abs() - 360.0).abs() < self.dlon.abs() * 0.5
+    }
+
+    /// Coordinate of the grid point at `index = j * ni + i`
+    pub fn coord_at(&self, index: usize) -> Coordinate {
+        let i = index % self.ni;
+        let j = index / self.ni;
+        Coordinate::new(self.lat0 + j as f64 * self.dlat, self.lon0 + i as f64 * self.dlon)
+    }
+
+    /// Returns `(min_lat, max_lat, min_lon, max_lon)`
+    pub fn bounds(&self) -> (f64, f64, f64, f64) {
+        let lat1 = self.lat0 + (self.nj.saturating_sub(1)) as f64 * self.dlat;
+        let lon1 = self.lon0 + (self.ni.saturating_sub(1)) as f64 * self.dlon;
+        (self.lat0.min(lat1), self.lat0.max(lat1), self.lon0.min(lon1), self.lon0.max(lon1))
+    }
+
+    /// Indices and weights of the four grid points surrounding `coord` for bilinear
+    /// interpolation. Returns `None` outside the grid.
+    pub fn bilinear_weights(&self, coord: &Coordinate) -> Option<[(usize, f32); 4]> {
+        const EPS: f64 = 1e-9;
+        if self.is_empty() || self.dlat == 0.0 || self.dlon == 0.0 {
             return None;
         }
-        
-        let mut min_lat = f64::MAX;
-        let mut max_lat = f64::MIN;
-        let mut min_lon = f64::MAX;
-        let mut max_lon = f64::MIN;
-        
-        for block in self.chunks.values() {
-            for (coord, _) in block {
-                if coord.lat < min_lat { min_lat = coord.lat; }
-                if coord.lat > max_lat { max_lat = coord.lat; }
-                if coord.lon < min_lon { min_lon = coord.lon; }
-                if coord.lon > max_lon { max_lon = coord.lon; }
-            }
+
+        let fj = (coord.lat - self.lat0) / self.dlat;
+        if fj < -EPS || fj > (self.nj - 1) as f64 + EPS {
+            return None;
         }
-        
-        Some((min_lat, max_lat, min_lon, max_lon))
+
+        // Longitude offset along the scanning direction, in [0, 360)
+        let d_lon = (coord.lon - self.lon0) * self.dlon.signum();
+        let fi = d_lon.rem_euclid(360.0) / self.dlon.abs();
+        let global = self.is_global();
+        let max_i = if global { self.ni as f64 } else { (self.ni - 1) as f64 };
+        if fi > max_i + EPS {
+            return None;
+        }
+
+        let fj = fj.clamp(0.0, (self.nj - 1) as f64);
+        let fi = fi.clamp(0.0, max_i);
+        let j0 = fj.floor() as usize;
+        let i0 = fi.floor() as usize;
+        let ty = (fj - j0 as f64) as f32;
+        let tx = (fi - i0 as f64) as f32;
+
+        let j0 = j0.min(self.nj - 1);
+        let j1 = (j0 + 1).min(self.nj - 1);
+        let (i0, i1) = if global {
+            (i0 % self.ni, (i0 + 1) % self.ni)
+        } else {
+            let i0 = i0.min(self.ni - 1);
+            (i0, (i0 + 1).min(self.ni - 1))
+        };
+
+        Some([
+            (j0 * self.ni + i0, (1.0 - tx) * (1.0 - ty)),
+            (j0 * self.ni + i1, tx * (1.0 - ty)),
+            (j1 * self.ni + i0, (1.0 - tx) * ty),
+            (j1 * self.ni + i1, tx * ty),
+        ])
     }
+}
 
-    /// Finds the nearest wind data point to the given coordinate
-    pub fn get_wind_at(&self, coord: &Coordinate) -> Option<WindData> {
-        let chunk_x = coord.lon.floor() as i32;
-        let chunk_y = coord.lat.floor() as i32;
-        
-        if let Some(chunk) = self.chunks.get(&(chunk_x, chunk_y)) {
-            // Find nearest neighbor in this chunk
-            let mut best_dist = f64::MAX;
-            let mut best_wind = None;
-            
-            for (p_coord, wind) in chunk {
-                let d_lat = p_coord.lat - coord.lat;
-                let d_lon = p_coord.lon - coord.lon;
-                let dist_sq = d_lat * d_lat + d_lon * d_lon;
-                
-                if dist_sq < best_dist {
-                    best_dist = dist_sq;
-                    best_wind = Some(*wind);
+/// How wind vectors are blended between grid points and forecast steps
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum WindInterpolation {
+    /// Interpolate the u/v components
+    #[default]
+    Components,
+    /// Interpolate the speed and the direction separately, which avoids the
+    /// speed drop between grid points with diverging wind directions
+    SpeedDirection,
+}
+
+impl WindData {
+    /// Weighted combination of wind vectors. Weights are expected to sum to 1.
+    pub fn blend(samples: &[(WindData, f32)], mode: WindInterpolation) -> WindData {
+        let mut u = 0.0;
+        let mut v = 0.0;
+        for (wind, weight) in samples {
+            u += wind.u * weight;
+            v += wind.v * weight;
+        }
+
+        if mode == WindInterpolation::SpeedDirection {
+            let mut speed = 0.0;
+            let mut dir_u = 0.0;
+            let mut dir_v = 0.0;
+            for (wind, weight) in samples {
+                let s = wind.speed();
+                speed += s * weight;
+                if s > 0.0 {
+                    dir_u += wind.u / s * weight;
+                    dir_v += wind.v / s * weight;
                 }
             }
-            return best_wind;
+
+            // Opposite directions cancel out, keep the component blend in that case
+            let dir_norm = (dir_u * dir_u + dir_v * dir_v).sqrt();
+            if dir_norm > 1e-6 {
+                u = speed * dir_u / dir_norm;
+                v = speed * dir_v / dir_norm;
+            }
         }
-        None
+
+        WindData { u, v }
+    }
+}
+
+/// A u/v vector field (wind, current) of a single forecast step, on a regular grid
+#[derive(Debug, Clone)]
+pub struct GridSnapshot {
+    /// Time at which this forecast step is valid
+    pub valid_time: DateTime<Utc>,
+    pub grid: GridGeometry,
+    /// U components (m/s), `grid.len()` values
+    pub u: Vec<f32>,
+    /// V components (m/s), `grid.len()` values
+    pub v: Vec<f32>,
+}
+
+impl GridSnapshot {
+    pub fn new(valid_time: DateTime<Utc>, grid: GridGeometry, u: Vec<f32>, v: Vec<f32>) -> Self {
+        Self { valid_time, grid, u, v }
+    }
+
+    pub fn point_count(&self) -> usize {
+        self.u.len().min(self.v.len())
+    }
+
+    pub fn get_bounds(&self) -> Option<(f64, f64, f64, f64)> {
+        if self.grid.is_empty() {
+            return None;
+        }
+        Some(self.grid.bounds())
+    }
+
+    /// Bilinear interpolation of the vector at the given coordinate, ignoring missing
+    /// (NaN) grid points. `None` when every surrounding grid point is missing.
+    pub fn sample(&self, coord: &Coordinate, mode: WindInterpolation) -> Option<WindData> {
+        let weights = self.grid.bilinear_weights(coord)?;
+        let mut samples = Vec::with_capacity(4);
+        let mut total_weight = 0.0;
+
+        for (idx, weight) in weights {
+            let (u, v) = (*self.u.get(idx)?, *self.v.get(idx)?);
+            if u.is_nan() || v.is_nan() || weight == 0.0 { continue; }
+            samples.push((WindData { u, v }, weight));
+            total_weight += weight;
+        }
+
+        if samples.is_empty() {
+            return None;
+        }
+        for sample in &mut samples {
+            sample.1 /= total_weight;
+        }
+        Some(WindData::blend(&samples, mode))
+    }
+}
+
+/// Inserts a forecast step into a series sorted by valid time, replacing any
+/// snapshot with the same valid time
+fn insert_sorted<T>(steps: &mut Vec<T>, step: T, valid_time: impl Fn(&T) -> DateTime<Utc>) {
+    match steps.binary_search_by_key(&valid_time(&step), &valid_time) {
+        Ok(idx) => steps[idx] = step,
+        Err(idx) => steps.insert(idx, step),
+    }
+}
+
+/// Finds the two forecast steps surrounding `time` in a series sorted by valid time,
+/// with the interpolation fraction between them. Returns `None` outside the forecast
+/// range, except for a single-step series which is used at any time.
+fn time_bracket<T>(
+    steps: &[T],
+    valid_time: impl Fn(&T) -> DateTime<Utc>,
+    time: DateTime<Utc>,
+) -> Option<(usize, usize, f32)> {
+    if steps.len() == 1 {
+        return Some((0, 0, 0.0));
+    }
+
+    let first = valid_time(steps.first()?);
+    let last = valid_time(steps.last()?);
+    if time < first || time > last {
+        return None;
+    }
+
+    // Index of the first step strictly after `time`
+    let next_idx = steps.partition_point(|s| valid_time(s) <= time);
+    if next_idx == steps.len() {
+        return Some((next_idx - 1, next_idx - 1, 0.0));
+    }
+
+    let before = valid_time(&steps[next_idx - 1]);
+    let after = valid_time(&steps[next_idx]);
+    let span = (after - before).num_milliseconds() as f32;
+    let t = (time - before).num_milliseconds() as f32 / span;
+
+    Some((next_idx - 1, next_idx, t))
+}
+
+/// Vector at `coord` valid at `time`, linearly interpolated between the two
+/// surrounding forecast steps
+fn sample_series(
+    snapshots: &[GridSnapshot],
+    coord: &Coordinate,
+    time: DateTime<Utc>,
+    mode: WindInterpolation,
+) -> Option<WindData> {
+    let (before, after, t) = time_bracket(snapshots, |s| s.valid_time, time)?;
+    let w0 = snapshots[before].sample(coord, mode)?;
+    if before == after {
+        return Some(w0);
+    }
+    let w1 = snapshots[after].sample(coord, mode)?;
+
+    Some(WindData::blend(&[(w0, 1.0 - t), (w1, t)], mode))
+}
+
+/// Global resource to hold the loaded wind forecast, one snapshot per forecast step
+#[derive(Resource, Default, Debug, Clone)]
+pub struct WindField {
+    /// Forecast steps sorted by valid time
+    pub snapshots: Vec<GridSnapshot>,
+    pub interpolation: WindInterpolation,
+    /// Set once an observation-based bias correction has been applied
+    pub bias_corrected: bool,
+}
+
+impl WindField {
+    /// Inserts a forecast step, replacing any snapshot with the same valid time
+    pub fn insert_snapshot(&mut self, snapshot: GridSnapshot) {
+        insert_sorted(&mut self.snapshots, snapshot, |s| s.valid_time);
+    }
+
+    /// First and last valid times of the forecast
+    pub fn time_range(&self) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        Some((self.snapshots.first()?.valid_time, self.snapshots.last()?.valid_time))
+    }
+    
+    pub fn get_bounds(&self) -> Option<(f64, f64, f64, f64)> {
+        self.snapshots.first()?.get_bounds()
+    }
+
+    /// Interpolates the wind in the first forecast step
+    pub fn get_wind_at(&self, coord: &Coordinate) -> Option<WindData> {
+        self.snapshots.first()?.sample(coord, self.interpolation)
+    }
+
+    /// Wind at `coord` valid at `time`, linearly interpolated between the two
+    /// surrounding forecast steps. Returns `None` outside the forecast range,
+    /// except for a single-step field which is used at any time.
+    pub fn wind_at(&self, coord: &Coordinate, time: DateTime<Utc>) -> Option<WindData> {
+        sample_series(&self.snapshots, coord, time, self.interpolation)
+    }
+}
+
+/// Global resource to hold the loaded surface current forecast, one snapshot per forecast step
+#[derive(Resource, Default, Debug, Clone)]
+pub struct CurrentField {
+    /// Forecast steps sorted by valid time, eastward/northward components (m/s)
+    pub snapshots: Vec<GridSnapshot>,
+}
+
+impl CurrentField {
+    /// Inserts a forecast step, replacing any snapshot with the same valid time
+    pub fn insert_snapshot(&mut self, snapshot: GridSnapshot) {
+        insert_sorted(&mut self.snapshots, snapshot, |s| s.valid_time);
+    }
+
+    /// First and last valid times of the forecast
+    pub fn time_range(&self) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        Some((self.snapshots.first()?.valid_time, self.snapshots.last()?.valid_time))
+    }
+
+    pub fn get_bounds(&self) -> Option<(f64, f64, f64, f64)> {
+        self.snapshots.first()?.get_bounds()
+    }
+
+    /// Current at `coord` valid at `time`, with the same spatial and temporal
+    /// interpolation as `WindField::wind_at`
+    pub fn current_at(&self, coord: &Coordinate, time: DateTime<Utc>) -> Option<CurrentData> {
+        sample_series(&self.snapshots, coord, time, WindInterpolation::Components)
+            .map(|v| CurrentData { u: v.u, v: v.v })
     }
 }
 
@@ -134,6 +414,153 @@ mod tests {
         
         let west_wind = WindData { u: 5.0, v: 0.0 };
         assert_eq!(west_wind.direction(), 270.0);
+
+        for dir in [0.0, 45.0, 90.0, 200.0, 315.0] {
+            let wind = WindData::from_speed_direction(8.0, dir);
+            assert!((wind.speed() - 8.0).abs() < 1e-4);
+            assert!((wind.direction() - dir).abs() < 1e-3, "{} != {}", wind.direction(), dir);
+        }
+    }
+
+    #[test]
+    fn test_longitude_normalisation() {
+        assert_eq!(Coordinate::new(10.0, 190.0).normalized().lon, -170.0);
+        assert_eq!(Coordinate::new(10.0, -540.0).normalized().lon, -180.0);
+        assert_eq!(Coordinate::new(10.0, 180.0).normalized().lon, -180.0);
+        assert_eq!(Coordinate::new(10.0, 45.0).normalized(), Coordinate::new(10.0, 45.0));
+
+        // Across the date line, positions follow on from the reference
+        assert_eq!(Coordinate::new(10.0, -179.0).unwrapped_around(179.0).lon, 181.0);
+        assert_eq!(Coordinate::new(10.0, 179.0).unwrapped_around(-179.0).lon, -181.0);
+    }
+
+    #[test]
+    fn test_wind_time_interpolation() {
+        let t0 = chrono::Utc::now();
+        let t1 = t0 + chrono::Duration::hours(6);
+        let coord = Coordinate::new(45.5, -1.5);
+        let grid = GridGeometry { lat0: 45.5, lon0: -1.5, dlat: 1.0, dlon: 1.0, ni: 1, nj: 1 };
+
+        let mut field = WindField::default();
+        field.insert_snapshot(GridSnapshot::new(t1, grid, vec![10.0], vec![-4.0]));
+        field.insert_snapshot(GridSnapshot::new(t0, grid, vec![0.0], vec![2.0]));
+
+        assert_eq!(field.time_range(), Some((t0, t1)));
+        assert_eq!(field.wind_at(&coord, t0), Some(WindData { u: 0.0, v: 2.0 }));
+        assert_eq!(field.wind_at(&coord, t1), Some(WindData { u: 10.0, v: -4.0 }));
+
+        let mid = field.wind_at(&coord, t0 + chrono::Duration::hours(3)).unwrap();
+        assert!((mid.u - 5.0).abs() < 1e-4);
+        assert!((mid.v + 1.0).abs() < 1e-4);
+
+        // Outside the forecast range
+        assert_eq!(field.wind_at(&coord, t1 + chrono::Duration::hours(1)), None);
+        assert_eq!(field.wind_at(&coord, t0 - chrono::Duration::hours(1)), None);
+    }
+
+    #[test]
+    fn test_current_lookup() {
+        let t0 = chrono::Utc::now();
+        let grid = GridGeometry { lat0: 50.0, lon0: -2.0, dlat: -0.5, dlon: 0.5, ni: 3, nj: 3 };
+
+        let mut field = CurrentField::default();
+        field.insert_snapshot(GridSnapshot::new(t0, grid, vec![1.0; 9], vec![-0.5; 9]));
+        field.insert_snapshot(GridSnapshot::new(t0 + chrono::Duration::hours(1), grid, vec![-1.0; 9], vec![0.5; 9]));
+
+        // Flood to ebb: slack water half way between the two steps
+        let coord = Coordinate::new(49.7, -1.3);
+        let slack = field.current_at(&coord, t0 + chrono::Duration::minutes(30)).unwrap();
+        assert!(slack.u.abs() < 1e-4 && slack.v.abs() < 1e-4);
+
+        assert_eq!(field.current_at(&coord, t0), Some(CurrentData { u: 1.0, v: -0.5 }));
+        assert_eq!(field.current_at(&Coordinate::new(48.0, -1.3), t0), None);
+
+        // Land points do not drag the current toward zero near the coast
+        let mut coastal = CurrentField::default();
+        let mut u = vec![1.0; 9];
+        u[0] = f32::NAN;
+        coastal.insert_snapshot(GridSnapshot::new(t0, grid, u, vec![0.0; 9]));
+        assert_eq!(coastal.current_at(&Coordinate::new(49.75, -1.75), t0), Some(CurrentData { u: 1.0, v: 0.0 }));
+        assert_eq!(coastal.current_at(&Coordinate::new(50.0, -2.0), t0), None);
+    }
+
+    #[test]
+    fn test_wave_lookup() {
+        let t0 = chrono::Utc::now();
+        let grid = GridGeometry { lat0: 44.0, lon0: -10.0, dlat: 1.0, dlon: 1.0, ni: 2, nj: 1 };
+
+        let mut field = WaveField::default();
+        field.insert_snapshot(WaveSnapshot {
+            valid_time: t0,
+            grid,
+            swh: vec![2.0, 4.0],
+            mwd: vec![350.0, 10.0],
+            mwp: vec![8.0, f32::NAN],
+        });
+
+        let state = field.sea_state_at(&Coordinate::new(44.0, -9.5), t0).unwrap();
+        assert!((state.significant_wave_height - 3.0).abs() < 1e-4);
+        // Directions are averaged across North, not to 180
+        let dir = state.mean_wave_direction.unwrap();
+        assert!(!(0.01..=359.99).contains(&dir), "Mean direction {}", dir);
+        // Missing period is left out of the average
+        assert_eq!(state.mean_wave_period, Some(8.0));
+
+        // Land point on one side only
+        field.snapshots[0].swh[1] = f32::NAN;
+        let state = field.sea_state_at(&Coordinate::new(44.0, -9.25), t0).unwrap();
+        assert_eq!(state.significant_wave_height, 2.0);
+    }
+
+    #[test]
+    fn test_wind_bilinear_interpolation() {
+        // 2x2 grid scanned North to South: rows at 46N and 45N, columns at 1W and 0E
+        let grid = GridGeometry { lat0: 46.0, lon0: -1.0, dlat: -1.0, dlon: 1.0, ni: 2, nj: 2 };
+        let snapshot = GridSnapshot::new(
+            chrono::Utc::now(),
+            grid,
+            vec![0.0, 10.0, 0.0, 10.0],
+            vec![0.0, 0.0, 4.0, 4.0],
+        );
+
+        let mode = WindInterpolation::Components;
+        let corner = snapshot.sample(&Coordinate::new(45.0, 0.0), mode).unwrap();
+        assert_eq!(corner, WindData { u: 10.0, v: 4.0 });
+
+        let center = snapshot.sample(&Coordinate::new(45.5, -0.5), mode).unwrap();
+        assert!((center.u - 5.0).abs() < 1e-4 && (center.v - 2.0).abs() < 1e-4);
+
+        let quarter = snapshot.sample(&Coordinate::new(45.75, -0.75), mode).unwrap();
+        assert!((quarter.u - 2.5).abs() < 1e-4 && (quarter.v - 1.0).abs() < 1e-4);
+
+        assert_eq!(snapshot.sample(&Coordinate::new(44.9, -0.5), mode), None);
+        assert_eq!(snapshot.sample(&Coordinate::new(45.5, 0.1), mode), None);
+        assert_eq!(grid.bounds(), (45.0, 46.0, -1.0, 0.0));
+    }
+
+    #[test]
+    fn test_wind_global_grid_wraps() {
+        // Four columns every 90 degrees starting at 0E
+        let grid = GridGeometry { lat0: 0.0, lon0: 0.0, dlat: 1.0, dlon: 90.0, ni: 4, nj: 1 };
+        assert!(grid.is_global());
+        let snapshot = GridSnapshot::new(chrono::Utc::now(), grid, vec![0.0, 0.0, 0.0, 8.0], vec![0.0; 4]);
+
+        // Halfway between 270E (-90) and 360E (0)
+        let wind = snapshot.sample(&Coordinate::new(0.0, -45.0), WindInterpolation::Components).unwrap();
+        assert!((wind.u - 4.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_wind_speed_direction_blend() {
+        let east = WindData { u: 10.0, v: 0.0 };
+        let north = WindData { u: 0.0, v: 10.0 };
+
+        let components = WindData::blend(&[(east, 0.5), (north, 0.5)], WindInterpolation::Components);
+        assert!((components.speed() - 7.071).abs() < 1e-3);
+
+        let polar = WindData::blend(&[(east, 0.5), (north, 0.5)], WindInterpolation::SpeedDirection);
+        assert!((polar.speed() - 10.0).abs() < 1e-3);
+        assert!((polar.direction() - 225.0).abs() < 1e-3);
     }
 }
 
@@ -146,7 +573,202 @@ pub struct CurrentData {
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct SeaState {
+    /// Significant height of combined wind waves and swell (m)
     pub significant_wave_height: f32,
+    /// Mean direction the waves come from (degrees, 0=North)
+    pub mean_wave_direction: Option<f32>,
+    /// Mean wave period (s)
+    pub mean_wave_period: Option<f32>,
+}
+
+impl SeaState {
+    /// Weighted combination of sea states. Directions are averaged on the circle;
+    /// samples without a direction or period are left out of that average.
+    pub fn blend(samples: &[(SeaState, f32)]) -> SeaState {
+        let mut height = 0.0;
+        let (mut dir_x, mut dir_y, mut dir_weight) = (0.0, 0.0, 0.0);
+        let (mut period, mut period_weight) = (0.0, 0.0);
+
+        for (state, weight) in samples {
+            height += state.significant_wave_height * weight;
+            if let Some(dir) = state.mean_wave_direction {
+                dir_x += dir.to_radians().sin() * weight;
+                dir_y += dir.to_radians().cos() * weight;
+                dir_weight += weight;
+            }
+            if let Some(p) = state.mean_wave_period {
+                period += p * weight;
+                period_weight += weight;
+            }
+        }
+
+        let mean_wave_direction = (dir_weight > 0.0 && (dir_x != 0.0 || dir_y != 0.0))
+            .then(|| dir_x.atan2(dir_y).to_degrees().rem_euclid(360.0));
+        let mean_wave_period = (period_weight > 0.0).then(|| period / period_weight);
+
+        SeaState { significant_wave_height: height, mean_wave_direction, mean_wave_period }
+    }
+}
+
+/// Wave parameters of a single forecast step, on a regular grid.
+/// Missing values (land points) are stored as NaN.
+#[derive(Debug, Clone)]
+pub struct WaveSnapshot {
+    /// Time at which this forecast step is valid
+    pub valid_time: DateTime<Utc>,
+    pub grid: GridGeometry,
+    /// Significant wave height (m), `grid.len()` values
+    pub swh: Vec<f32>,
+    /// Mean wave direction (degrees), empty if not provided
+    pub mwd: Vec<f32>,
+    /// Mean wave period (s), empty if not provided
+    pub mwp: Vec<f32>,
+}
+
+impl WaveSnapshot {
+    /// Bilinear interpolation of the sea state, ignoring missing grid points
+    pub fn sample(&self, coord: &Coordinate) -> Option<SeaState> {
+        let weights = self.grid.bilinear_weights(coord)?;
+        let mut samples = Vec::with_capacity(4);
+        let mut total_weight = 0.0;
+
+        for (idx, weight) in weights {
+            let swh = *self.swh.get(idx)?;
+            if swh.is_nan() || weight == 0.0 { continue; }
+            let value = |values: &[f32]| values.get(idx).copied().filter(|v| !v.is_nan());
+            samples.push((SeaState {
+                significant_wave_height: swh,
+                mean_wave_direction: value(&self.mwd),
+                mean_wave_period: value(&self.mwp),
+            }, weight));
+            total_weight += weight;
+        }
+
+        if samples.is_empty() {
+            return None;
+        }
+        for sample in &mut samples {
+            sample.1 /= total_weight;
+        }
+        Some(SeaState::blend(&samples))
+    }
+}
+
+/// Global resource to hold the loaded wave forecast, one snapshot per forecast step
+#[derive(Resource, Default, Debug, Clone)]
+pub struct WaveField {
+    /// Forecast steps sorted by valid time
+    pub snapshots: Vec<WaveSnapshot>,
+}
+
+impl WaveField {
+    /// Inserts a forecast step, replacing any snapshot with the same valid time
+    pub fn insert_snapshot(&mut self, snapshot: WaveSnapshot) {
+        insert_sorted(&mut self.snapshots, snapshot, |s| s.valid_time);
+    }
+
+    /// First and last valid times of the forecast
+    pub fn time_range(&self) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        Some((self.snapshots.first()?.valid_time, self.snapshots.last()?.valid_time))
+    }
+
+    /// Sea state at `coord` valid at `time`, with the same spatial and temporal
+    /// interpolation as `WindField::wind_at`
+    pub fn sea_state_at(&self, coord: &Coordinate, time: DateTime<Utc>) -> Option<SeaState> {
+        let (before, after, t) = time_bracket(&self.snapshots, |s| s.valid_time, time)?;
+        let s0 = self.snapshots[before].sample(coord)?;
+        if before == after {
+            return Some(s0);
+        }
+        let s1 = self.snapshots[after].sample(coord)?;
+
+        Some(SeaState::blend(&[(s0, 1.0 - t), (s1, t)]))
+    }
+}
+
+/// Platform that produced a wind observation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ObservationSource {
+    Buoy,
+    Ship,
+    /// Satellite scatterometer (ASCAT)
+    Scatterometer,
+    Other,
+}
+
+/// A point wind observation
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindObservation {
+    pub source: ObservationSource,
+    pub position: Coordinate,
+    pub time: DateTime<Utc>,
+    pub wind: WindData,
+}
+
+/// Global resource to hold the loaded wind observations
+#[derive(Resource, Default, Debug, Clone)]
+pub struct ObservationSet {
+    pub observations: Vec<WindObservation>,
+}
+
+impl ObservationSet {
+    /// Mean (observed - forecast) wind speed in m/s over the observations covered
+    /// by the forecast, with the number of observations compared
+    pub fn mean_speed_error(&self, wind_field: &WindField) -> Option<(f32, usize)> {
+        let mut sum = 0.0;
+        let mut count = 0;
+        for obs in &self.observations {
+            if let Some(forecast) = wind_field.wind_at(&obs.position, obs.time) {
+                sum += obs.wind.speed() - forecast.speed();
+                count += 1;
+            }
+        }
+        (count > 0).then(|| (sum / count as f32, count))
+    }
+}
+
+/// Side the wind comes over. On starboard tack the wind blows over the starboard (right) side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tack {
+    Port,
+    Starboard,
+}
+
+impl Tack {
+    /// Tack from a signed TWA (`TWD - heading`, positive = wind from starboard)
+    pub fn from_signed_twa(twa: f32) -> Self {
+        if twa >= 0.0 { Tack::Starboard } else { Tack::Port }
+    }
+}
+
+impl std::fmt::Display for Tack {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Tack::Port => write!(f, "port"),
+            Tack::Starboard => write!(f, "starboard"),
+        }
+    }
+}
+
+/// How a front point was reached from its parent on the previous front
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lineage {
+    /// Index of the parent state in the previous front
+    pub parent_index: usize,
+    /// True heading steered from the parent (degrees)
+    pub heading: f32,
+    /// True Wind Speed at the parent (m/s)
+    pub tws: f32,
+    /// True Wind Angle sailed on the leg (degrees)
+    pub twa: f32,
+    /// Tack sailed on the leg
+    pub tack: Tack,
+    /// Speed over ground (m/s)
+    pub sog: f32,
+    /// Course over ground (degrees)
+    pub cog: f32,
+    /// Index of the sail used on the leg, when the polar distinguishes sails
+    pub sail: Option<usize>,
 }
 
 /// The state of the boat at a specific point in time
@@ -156,4 +778,64 @@ pub struct BoatState {
     pub time: chrono::DateTime<chrono::Utc>,
     /// Elapsed time since departure in seconds
     pub elapsed_time: f64,
+    /// Link to the parent state, `None` for the departure point
+    pub lineage: Option<Lineage>,
+}
+
+impl BoatState {
+    /// Creates a departure state with no lineage
+    pub fn new(position: Coordinate, time: chrono::DateTime<chrono::Utc>) -> Self {
+        Self {
+            position,
+            time,
+            elapsed_time: 0.0,
+            lineage: None,
+        }
+    }
+
+    /// Tack the boat arrived on, `None` for the departure point
+    pub fn tack(&self) -> Option<Tack> {
+        self.lineage.map(|lineage| lineage.tack)
+    }
+}
+
+/// A single leg of a reconstructed route, between two consecutive fronts
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouteLeg {
+    pub from: Coordinate,
+    pub to: Coordinate,
+    pub start_time: chrono::DateTime<chrono::Utc>,
+    pub end_time: chrono::DateTime<chrono::Utc>,
+    /// True heading steered on the leg (degrees)
+    pub heading: f32,
+    /// True Wind Speed at the start of the leg (m/s)
+    pub tws: f32,
+    /// True Wind Angle sailed on the leg (degrees)
+    pub twa: f32,
+    pub tack: Tack,
+    /// Speed over ground (m/s)
+    pub sog: f32,
+    /// Course over ground (degrees)
+    pub cog: f32,
+    /// Index of the sail used on the leg, when the polar distinguishes sails
+    pub sail: Option<usize>,
+}
+
+impl RouteLeg {
+    /// Short description of the point of sail, e.g. "port tack, TWA 120"
+    pub fn annotation(&self) -> String {
+        format!("{} tack, TWA {:.0}", self.tack, self.twa.abs())
+    }
+
+    /// Times at which a different sail is needed along the route, starting with the first sail
+    pub fn sail_changes(legs: &[RouteLeg]) -> Vec<(chrono::DateTime<chrono::Utc>, usize)> {
+        let mut changes: Vec<(chrono::DateTime<chrono::Utc>, usize)> = Vec::new();
+        for leg in legs {
+            let Some(sail) = leg.sail else { continue };
+            if changes.last().is_none_or(|&(_, current)| current != sail) {
+                changes.push((leg.start_time, sail));
+            }
+        }
+        changes
+    }
 }
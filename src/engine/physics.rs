@@ -1,42 +1,219 @@
-use crate::engine::models::{WindData, CurrentData, SeaState};
+use bevy::prelude::Resource;
+use chrono::{DateTime, Datelike, Timelike, Utc};
+
+use crate::engine::models::{BoatState, Coordinate, WindData, CurrentData, SeaState, Tack};
 use crate::parsers::polars::PolarData;
 
-pub struct PhysicsModel;
+/// Speed reduction in waves. The fractional loss grows linearly with the significant
+/// wave height above `threshold`, scaled by the relative wave angle (head, beam or
+/// following seas) and capped at `max_reduction`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WavePenalty {
+    /// Fractional speed loss per metre of wave height above the threshold, in head seas
+    pub loss_per_meter: f32,
+    /// Waves below this significant height do not slow the boat (m)
+    pub threshold: f32,
+    /// Loss in beam seas relative to head seas
+    pub beam_factor: f32,
+    /// Loss in following seas relative to head seas
+    pub following_factor: f32,
+    /// Maximum fractional speed loss
+    pub max_reduction: f32,
+}
+
+impl Default for WavePenalty {
+    fn default() -> Self {
+        Self {
+            loss_per_meter: 0.08,
+            threshold: 1.0,
+            beam_factor: 0.5,
+            following_factor: 0.2,
+            max_reduction: 0.6,
+        }
+    }
+}
+
+impl WavePenalty {
+    /// Fraction of the polar speed kept in the given sea state.
+    /// `relative_angle` is the angle between the wave origin and the heading (0 = head seas).
+    pub fn speed_factor(&self, significant_wave_height: f32, relative_angle: f32) -> f32 {
+        let height = (significant_wave_height - self.threshold).max(0.0);
+        let cos = relative_angle.to_radians().cos();
+        let angle_factor = self.beam_factor
+            + (1.0 - self.beam_factor) * cos.max(0.0)
+            + (self.following_factor - self.beam_factor) * (-cos).max(0.0);
+
+        let reduction = (self.loss_per_meter * height * angle_factor).clamp(0.0, self.max_reduction);
+        1.0 - reduction
+    }
+}
+
+/// Efficiency override for a range of wind speeds and angles (bounds inclusive)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EfficiencyBand {
+    /// True wind speed range (knots)
+    pub tws: (f32, f32),
+    /// True wind angle range (degrees, 0-180)
+    pub twa: (f32, f32),
+    /// Fraction of the polar speed achieved in this band
+    pub factor: f32,
+}
+
+/// Fraction of the polar speed actually achieved. The first matching band overrides
+/// the global factor, and the night factor applies on top once the sun is below
+/// `night_elevation`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolarEfficiency {
+    pub global: f32,
+    pub bands: Vec<EfficiencyBand>,
+    /// Extra factor at night, for skippers sailing solo or short-handed
+    pub night_factor: f32,
+    /// Solar elevation under which it is considered night (degrees)
+    pub night_elevation: f32,
+}
+
+impl Default for PolarEfficiency {
+    fn default() -> Self {
+        Self {
+            global: 1.0,
+            bands: Vec::new(),
+            night_factor: 1.0,
+            night_elevation: -6.0, // Civil twilight
+        }
+    }
+}
+
+impl PolarEfficiency {
+    /// Typical short-handed settings: 90% of polar, 85% in light air, 95% reaching,
+    /// and a further 10% lost at night
+    pub fn short_handed() -> Self {
+        Self {
+            global: 0.9,
+            bands: vec![
+                EfficiencyBand { tws: (0.0, 8.0), twa: (0.0, 180.0), factor: 0.85 },
+                EfficiencyBand { tws: (0.0, 100.0), twa: (70.0, 130.0), factor: 0.95 },
+            ],
+            night_factor: 0.9,
+            ..Default::default()
+        }
+    }
+
+    /// Speed factor for the given conditions (TWS in knots, absolute TWA in degrees)
+    pub fn factor(&self, tws: f32, twa: f32, position: &Coordinate, time: DateTime<Utc>) -> f32 {
+        let base = self.bands.iter()
+            .find(|band| (band.tws.0..=band.tws.1).contains(&tws) && (band.twa.0..=band.twa.1).contains(&twa))
+            .map_or(self.global, |band| band.factor);
+
+        if self.night_factor != 1.0 && solar_elevation(position, time) < self.night_elevation {
+            base * self.night_factor
+        } else {
+            base
+        }
+    }
+}
+
+/// Solar elevation above the horizon in degrees (NOAA approximation, ~0.5° accuracy)
+pub fn solar_elevation(position: &Coordinate, time: DateTime<Utc>) -> f32 {
+    let hours = time.hour() as f64 + time.minute() as f64 / 60.0 + time.second() as f64 / 3600.0;
+    let gamma = 2.0 * std::f64::consts::PI / 365.0 * (time.ordinal0() as f64 + (hours - 12.0) / 24.0);
+
+    let eq_time = 229.18 * (0.000075 + 0.001868 * gamma.cos() - 0.032077 * gamma.sin()
+        - 0.014615 * (2.0 * gamma).cos() - 0.040849 * (2.0 * gamma).sin());
+    let declination = 0.006918 - 0.399912 * gamma.cos() + 0.070257 * gamma.sin()
+        - 0.006758 * (2.0 * gamma).cos() + 0.000907 * (2.0 * gamma).sin()
+        - 0.002697 * (3.0 * gamma).cos() + 0.00148 * (3.0 * gamma).sin();
+
+    // True solar time (minutes) and hour angle
+    let solar_time = hours * 60.0 + eq_time + 4.0 * position.lon;
+    let hour_angle = (solar_time / 4.0 - 180.0).to_radians();
+
+    let lat = position.lat.to_radians();
+    let cos_zenith = lat.sin() * declination.sin() + lat.cos() * declination.cos() * hour_angle.cos();
+    (90.0 - cos_zenith.clamp(-1.0, 1.0).acos().to_degrees()) as f32
+}
+
+/// Boat motion over ground for a given heading, with the wind angle it was sailed at
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SailingVector {
+    /// Speed over ground (m/s)
+    pub sog: f32,
+    /// Course over ground (degrees)
+    pub cog: f32,
+    /// Signed True Wind Angle (degrees, positive = starboard tack)
+    pub twa: f32,
+    pub tack: Tack,
+    /// Fastest sail at this angle, when the polar is a sail set
+    pub sail: Option<usize>,
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct PhysicsModel {
+    pub wave_penalty: WavePenalty,
+    pub efficiency: PolarEfficiency,
+}
+
+impl Default for PhysicsModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl PhysicsModel {
     pub fn new() -> Self {
-        Self
+        Self {
+            wave_penalty: WavePenalty::default(),
+            efficiency: PolarEfficiency::default(),
+        }
     }
 
-    /// Calculates True Wind Angle (TWA) from True Wind Direction (TWD) and Boat Heading
-    pub fn calculate_twa(twd: f32, heading: f32) -> f32 {
+    /// Calculates the signed True Wind Angle in (-180, 180] from True Wind Direction (TWD)
+    /// and Boat Heading. Positive when the wind comes over the starboard side.
+    pub fn signed_twa(twd: f32, heading: f32) -> f32 {
         let mut twa = twd - heading;
         while twa > 180.0 { twa -= 360.0; }
-        while twa < -180.0 { twa += 360.0; }
-        twa.abs() // Usually polars are symmetric
+        while twa <= -180.0 { twa += 360.0; }
+        twa
+    }
+
+    /// Calculates True Wind Angle (TWA) from True Wind Direction (TWD) and Boat Heading
+    pub fn calculate_twa(twd: f32, heading: f32) -> f32 {
+        Self::signed_twa(twd, heading).abs() // Usually polars are symmetric
     }
 
-    /// Computes the boat speed over Ground (SOG) and Course over Ground (COG)
+    /// Computes the boat speed over Ground (SOG) and Course over Ground (COG) from `state`
     pub fn compute_vector(
         &self,
         heading: f32, // true heading (0=North, 90=East)
+        state: &BoatState,
         wind: &WindData,
         current: &CurrentData,
         polar: &PolarData,
-        _sea_state: Option<&SeaState>
-    ) -> (f32, f32) { // returns (SOG in m/s, COG in degrees)
+        sea_state: Option<&SeaState>
+    ) -> SailingVector {
         // 1. Calculate TWS and TWD from WindData components
         let tws_ms = wind.speed();
         let twd = wind.direction();
 
-        // 2. Calculate TWA
-        let twa = Self::calculate_twa(twd, heading);
+        // 2. Calculate TWA, keeping the tack side
+        let signed_twa = Self::signed_twa(twd, heading);
+        let tack = Tack::from_signed_twa(signed_twa);
 
         // 3. Lookup Boat Speed through water (STW) from polars
         // Convert TWS to knots for polar lookup
         let tws_kts = tws_ms * 1.94384;
-        let stw_kts = polar.get_speed(tws_kts, twa);
-        let stw = stw_kts / 1.94384; // back to m/s
+        let (sail, stw_kts) = match polar.best_sail(tws_kts, signed_twa.abs()) {
+            Some((sail, speed)) => (Some(sail), speed),
+            None => (None, polar.get_speed(tws_kts, signed_twa.abs())),
+        };
+        let efficiency = self.efficiency.factor(tws_kts, signed_twa.abs(), &state.position, state.time);
+        let mut stw = stw_kts * efficiency / 1.94384; // back to m/s
+
+        // 3b. Slow down in waves. Without a wave direction, assume a wind sea.
+        if let Some(sea) = sea_state {
+            let wave_from = sea.mean_wave_direction.unwrap_or(twd);
+            let relative_angle = Self::calculate_twa(wave_from, heading);
+            stw *= self.wave_penalty.speed_factor(sea.significant_wave_height, relative_angle);
+        }
 
         // 4. Calculate boat velocity vector (East, North) relative to water
         let heading_rad = (heading as f64).to_radians();
@@ -52,7 +229,7 @@ impl PhysicsModel {
         let mut cog = sog_x.atan2(sog_y).to_degrees() as f32;
         if cog < 0.0 { cog += 360.0; }
 
-        (sog, cog)
+        SailingVector { sog, cog, twa: signed_twa, tack, sail }
     }
 }
 
@@ -78,9 +255,80 @@ mod tests {
         assert_eq!(PhysicsModel::calculate_twa(180.0, 150.0), 30.0);
     }
 
+    #[test]
+    fn test_signed_twa() {
+        // Wind from North, heading East: wind over the port side
+        assert_eq!(PhysicsModel::signed_twa(0.0, 90.0), -90.0);
+        assert_eq!(Tack::from_signed_twa(PhysicsModel::signed_twa(0.0, 90.0)), Tack::Port);
+        // Wind from North, heading West: starboard tack
+        assert_eq!(PhysicsModel::signed_twa(0.0, 270.0), 90.0);
+        assert_eq!(PhysicsModel::signed_twa(350.0, 20.0), -30.0);
+        assert_eq!(PhysicsModel::signed_twa(0.0, 180.0), 180.0);
+
+        let physics = PhysicsModel::new();
+        let state = BoatState::new(Coordinate::new(45.0, -5.0), chrono::Utc::now());
+        let mut polar = PolarData::default();
+        polar.tws = vec![0.0, 10.0];
+        polar.twa = vec![0.0, 180.0];
+        polar.speeds = vec![vec![0.0, 10.0], vec![0.0, 10.0]];
+        let wind = WindData { u: 0.0, v: -5.144 }; // From North
+        let current = CurrentData { u: 0.0, v: 0.0 };
+
+        let port = physics.compute_vector(120.0, &state, &wind, &current, &polar, None);
+        let starboard = physics.compute_vector(240.0, &state, &wind, &current, &polar, None);
+        assert_eq!((port.tack, port.twa), (Tack::Port, -120.0));
+        assert_eq!((starboard.tack, starboard.twa), (Tack::Starboard, 120.0));
+        assert!((port.sog - starboard.sog).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_solar_elevation() {
+        use chrono::TimeZone;
+        let equator = Coordinate::new(0.0, 0.0);
+        let noon = Utc.with_ymd_and_hms(2025, 3, 20, 12, 7, 0).unwrap();
+        assert!(solar_elevation(&equator, noon) > 88.0);
+        assert!(solar_elevation(&equator, noon + chrono::Duration::hours(12)) < -88.0);
+
+        // Brest at the winter solstice: low sun at noon, night at 20:00 UTC
+        let brest = Coordinate::new(48.4, -4.5);
+        let winter_noon = Utc.with_ymd_and_hms(2025, 12, 21, 12, 30, 0).unwrap();
+        assert!((solar_elevation(&brest, winter_noon) - 18.2).abs() < 1.0);
+        assert!(solar_elevation(&brest, winter_noon + chrono::Duration::hours(8)) < -6.0);
+    }
+
+    #[test]
+    fn test_polar_efficiency() {
+        use chrono::TimeZone;
+        let efficiency = PolarEfficiency::short_handed();
+        let position = Coordinate::new(48.4, -4.5);
+        let day = Utc.with_ymd_and_hms(2025, 6, 21, 12, 0, 0).unwrap();
+        let night = Utc.with_ymd_and_hms(2025, 6, 21, 1, 0, 0).unwrap();
+
+        assert_eq!(efficiency.factor(5.0, 90.0, &position, day), 0.85); // Light air band first
+        assert_eq!(efficiency.factor(15.0, 90.0, &position, day), 0.95); // Reaching
+        assert_eq!(efficiency.factor(15.0, 45.0, &position, day), 0.9); // Global
+        assert!((efficiency.factor(15.0, 45.0, &position, night) - 0.81).abs() < 1e-6);
+        assert_eq!(PolarEfficiency::default().factor(15.0, 45.0, &position, night), 1.0);
+
+        let mut physics = PhysicsModel::new();
+        let mut polar = PolarData::default();
+        polar.tws = vec![0.0, 20.0];
+        polar.twa = vec![0.0, 180.0];
+        polar.speeds = vec![vec![10.0, 10.0], vec![10.0, 10.0]];
+        let state = BoatState::new(position, day);
+        let wind = WindData { u: 0.0, v: -7.7 }; // From North, 15 knots
+        let current = CurrentData { u: 0.0, v: 0.0 };
+
+        let full = physics.compute_vector(45.0, &state, &wind, &current, &polar, None).sog;
+        physics.efficiency = efficiency;
+        let reduced = physics.compute_vector(45.0, &state, &wind, &current, &polar, None).sog;
+        assert!((reduced / full - 0.9).abs() < 1e-4);
+    }
+
     #[test]
     fn test_compute_vector_no_current() {
         let physics = PhysicsModel::new();
+        let state = BoatState::new(Coordinate::new(45.0, -5.0), chrono::Utc::now());
         let mut polar = PolarData::default();
         polar.tws = vec![0.0, 10.0];
         polar.twa = vec![0.0, 180.0];
@@ -90,21 +338,50 @@ mod tests {
         let current = CurrentData { u: 0.0, v: 0.0 };
 
         // Heading East (90)
-        let (sog, cog) = physics.compute_vector(90.0, &wind, &current, &polar, None);
+        let SailingVector { sog, cog, .. } = physics.compute_vector(90.0, &state, &wind, &current, &polar, None);
         
         // stw = 10 knots = 5.144 m/s
         assert!((sog - 5.144).abs() < 0.01);
         assert!((cog - 90.0).abs() < 0.1);
 
         // Heading North (0)
-        let (sog, cog) = physics.compute_vector(0.0, &wind, &current, &polar, None);
+        let SailingVector { sog, cog, .. } = physics.compute_vector(0.0, &state, &wind, &current, &polar, None);
         assert!((sog - 5.144).abs() < 0.01);
         assert!((cog - 0.0).abs() < 0.1);
     }
 
+    #[test]
+    fn test_compute_vector_in_waves() {
+        let physics = PhysicsModel::new();
+        let state = BoatState::new(Coordinate::new(45.0, -5.0), chrono::Utc::now());
+        let mut polar = PolarData::default();
+        polar.tws = vec![0.0, 10.0];
+        polar.twa = vec![0.0, 180.0];
+        polar.speeds = vec![vec![0.0, 10.0], vec![0.0, 10.0]];
+
+        let wind = WindData { u: 0.0, v: -5.144 }; // From North, 10 knots
+        let current = CurrentData { u: 0.0, v: 0.0 };
+        let sea = SeaState { significant_wave_height: 5.0, mean_wave_direction: Some(0.0), mean_wave_period: Some(9.0) };
+
+        let calm = physics.compute_vector(0.0, &state, &wind, &current, &polar, None).sog;
+        let head = physics.compute_vector(0.0, &state, &wind, &current, &polar, Some(&sea)).sog;
+        let beam = physics.compute_vector(90.0, &state, &wind, &current, &polar, Some(&sea)).sog;
+        let following = physics.compute_vector(180.0, &state, &wind, &current, &polar, Some(&sea)).sog;
+
+        // 4 m above threshold * 8% = 32% loss in head seas
+        assert!((head / calm - 0.68).abs() < 1e-3, "Head sea factor {}", head / calm);
+        assert!(head < beam && beam < following && following < calm);
+
+        // Small waves are ignored
+        let flat = SeaState { significant_wave_height: 0.8, mean_wave_direction: None, mean_wave_period: None };
+        let sog = physics.compute_vector(0.0, &state, &wind, &current, &polar, Some(&flat)).sog;
+        assert_eq!(sog, calm);
+    }
+
     #[test]
     fn test_compute_vector_with_current() {
         let physics = PhysicsModel::new();
+        let state = BoatState::new(Coordinate::new(45.0, -5.0), chrono::Utc::now());
         let mut polar = PolarData::default();
         polar.tws = vec![0.0, 10.0];
         polar.twa = vec![0.0, 180.0];
@@ -114,7 +391,7 @@ mod tests {
         let current = CurrentData { u: 2.0, v: 0.0 }; // 2 m/s East current
 
         // Heading North (0)
-        let (sog, cog) = physics.compute_vector(0.0, &wind, &current, &polar, None);
+        let SailingVector { sog, cog, .. } = physics.compute_vector(0.0, &state, &wind, &current, &polar, None);
         
         // stw = 0 (no wind), so we just drift with current
         assert!((sog - 2.0).abs() < 0.1);
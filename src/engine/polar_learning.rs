@@ -0,0 +1,288 @@
+use log::info;
+
+use crate::parsers::boat_log::LogSample;
+use crate::parsers::polars::PolarData;
+
+/// Normal-consistent scale of the median absolute deviation
+const MAD_SCALE: f32 = 1.4826;
+
+/// Fits an empirical polar from the boat's log on a fixed TWS/TWA grid.
+///
+/// Only steady-state samples are kept (wind and boat speed stable over the preceding
+/// `steady_window`, no tack or gybe), each is binned into the nearest grid cell, and
+/// outliers are rejected per cell with a median/MAD test before averaging.
+#[derive(Debug, Clone)]
+pub struct PolarLearner {
+    /// Grid wind speeds (knots)
+    pub tws: Vec<f32>,
+    /// Grid wind angles (degrees)
+    pub twa: Vec<f32>,
+    /// Length of history that must be steady before a sample is used (seconds)
+    pub steady_window: f64,
+    /// Maximum TWS (knots), signed TWA (degrees) and STW (knots) spread over the window
+    pub max_tws_spread: f32,
+    pub max_twa_spread: f32,
+    pub max_stw_spread: f32,
+    /// Samples further than this many robust standard deviations from the cell median are dropped
+    pub outlier_threshold: f32,
+    /// Cells with fewer samples are not trusted
+    pub min_samples: usize,
+}
+
+/// Polar fitted from the log, with the number of samples behind every cell
+#[derive(Debug, Clone)]
+pub struct LearnedPolar {
+    /// Measured speeds, 0 where a cell has no sample
+    pub polar: PolarData,
+    /// Samples kept per cell: counts[twa_idx][tws_idx]
+    pub counts: Vec<Vec<usize>>,
+    pub min_samples: usize,
+    /// Samples in the log, and samples that passed the steady-state filter
+    pub total_samples: usize,
+    pub steady_samples: usize,
+}
+
+impl PolarLearner {
+    pub fn new(tws: Vec<f32>, twa: Vec<f32>) -> Self {
+        Self {
+            tws,
+            twa,
+            steady_window: 30.0,
+            max_tws_spread: 3.0,
+            max_twa_spread: 10.0,
+            max_stw_spread: 1.0,
+            outlier_threshold: 3.0,
+            min_samples: 5,
+        }
+    }
+
+    /// Learner on the same grid as a theoretical polar, so both can be compared cell by cell
+    pub fn for_polar(polar: &PolarData) -> Self {
+        Self::new(polar.tws.clone(), polar.twa.clone())
+    }
+
+    /// Samples preceded by at least `steady_window` seconds of stable conditions.
+    /// `samples` must be in time order.
+    pub fn steady_samples<'a>(&self, samples: &'a [LogSample]) -> Vec<&'a LogSample> {
+        let min_history = self.steady_window * 0.8;
+        let mut start = 0;
+        let mut steady = Vec::new();
+
+        for (i, sample) in samples.iter().enumerate() {
+            while (sample.time - samples[start].time).num_milliseconds() as f64 / 1000.0 > self.steady_window {
+                start += 1;
+            }
+            let window = &samples[start..=i];
+            if ((sample.time - window[0].time).num_milliseconds() as f64 / 1000.0) < min_history {
+                continue;
+            }
+
+            let spread = |value: fn(&LogSample) -> f32| {
+                let (min, max) = window.iter().map(value).fold((f32::MAX, f32::MIN), |(min, max), v| (min.min(v), max.max(v)));
+                max - min
+            };
+            if spread(|s| s.tws) <= self.max_tws_spread && spread(|s| s.twa) <= self.max_twa_spread && spread(|s| s.stw) <= self.max_stw_spread {
+                steady.push(sample);
+            }
+        }
+        steady
+    }
+
+    /// Index of the grid value closest to `value`
+    fn nearest(axis: &[f32], value: f32) -> Option<usize> {
+        axis.iter()
+            .enumerate()
+            .min_by(|a, b| (a.1 - value).abs().total_cmp(&(b.1 - value).abs()))
+            .map(|(i, _)| i)
+    }
+
+    pub fn fit(&self, samples: &[LogSample]) -> LearnedPolar {
+        let steady = self.steady_samples(samples);
+
+        let mut cells = vec![vec![Vec::new(); self.tws.len()]; self.twa.len()];
+        for sample in &steady {
+            if let (Some(j), Some(i)) = (Self::nearest(&self.tws, sample.tws), Self::nearest(&self.twa, sample.twa.abs())) {
+                cells[i][j].push(sample.stw);
+            }
+        }
+
+        let mut counts = vec![vec![0; self.tws.len()]; self.twa.len()];
+        let mut speeds = vec![vec![0.0; self.tws.len()]; self.twa.len()];
+        for (i, row) in cells.iter_mut().enumerate() {
+            for (j, values) in row.iter_mut().enumerate() {
+                let inliers = self.reject_outliers(values);
+                if !inliers.is_empty() {
+                    counts[i][j] = inliers.len();
+                    speeds[i][j] = inliers.iter().sum::<f32>() / inliers.len() as f32;
+                }
+            }
+        }
+
+        info!("Fitted polar from {} steady samples out of {}", steady.len(), samples.len());
+        LearnedPolar {
+            polar: PolarData { tws: self.tws.clone(), twa: self.twa.clone(), speeds, ..Default::default() },
+            counts,
+            min_samples: self.min_samples,
+            total_samples: samples.len(),
+            steady_samples: steady.len(),
+        }
+    }
+
+    /// Drops values further than `outlier_threshold` robust deviations from the median
+    fn reject_outliers(&self, values: &mut [f32]) -> Vec<f32> {
+        if values.is_empty() {
+            return Vec::new();
+        }
+        let median = |sorted: &[f32]| {
+            let n = sorted.len();
+            if n % 2 == 1 { sorted[n / 2] } else { 0.5 * (sorted[n / 2 - 1] + sorted[n / 2]) }
+        };
+
+        values.sort_by(f32::total_cmp);
+        let center = median(values);
+        let mut deviations: Vec<f32> = values.iter().map(|v| (v - center).abs()).collect();
+        deviations.sort_by(f32::total_cmp);
+        let sigma = MAD_SCALE * median(&deviations);
+
+        if sigma <= f32::EPSILON {
+            // More than half the samples agree exactly: keep those
+            return values.iter().copied().filter(|v| (v - center).abs() <= f32::EPSILON).collect();
+        }
+        values.iter().copied().filter(|v| (v - center).abs() <= self.outlier_threshold * sigma).collect()
+    }
+}
+
+impl LearnedPolar {
+    /// True when the cell has enough samples to be trusted
+    pub fn is_reliable(&self, twa_idx: usize, tws_idx: usize) -> bool {
+        self.counts[twa_idx][tws_idx] >= self.min_samples
+    }
+
+    /// Routing polar: measured speeds where reliable, `theoretical` elsewhere.
+    /// A theoretical sail set is kept on the learned grid, every sail scaled by the
+    /// measured / theoretical ratio of the cell, so sail choice follows the theoretical crossovers.
+    pub fn merged_with(&self, theoretical: &PolarData) -> PolarData {
+        let grid = |value: &dyn Fn(usize, usize, f32, f32) -> f32| -> Vec<Vec<f32>> {
+            self.polar.twa.iter().enumerate()
+                .map(|(i, &twa)| self.polar.tws.iter().enumerate().map(|(j, &tws)| value(i, j, tws, twa)).collect())
+                .collect()
+        };
+        let speeds = grid(&|i, j, tws, twa| if self.is_reliable(i, j) { self.polar.speeds[i][j] } else { theoretical.get_speed(tws, twa) });
+
+        let scale = |i: usize, j: usize, tws: f32, twa: f32| {
+            let expected = theoretical.get_speed(tws, twa);
+            if self.is_reliable(i, j) && expected > 0.0 { self.polar.speeds[i][j] / expected } else { 1.0 }
+        };
+        let sail_speeds = (0..theoretical.sails.len())
+            .map(|sail| grid(&|i, j, tws, twa| scale(i, j, tws, twa) * theoretical.sail_speed(sail, tws, twa)))
+            .collect();
+
+        PolarData {
+            speeds,
+            sails: theoretical.sails.clone(),
+            sail_speeds,
+            ..self.polar.clone()
+        }
+    }
+
+    /// Measured / theoretical speed for every reliable cell (1.1 = 10% faster than the polar)
+    pub fn performance(&self, theoretical: &PolarData) -> Vec<Vec<Option<f32>>> {
+        self.polar.twa.iter().enumerate()
+            .map(|(i, &twa)| self.polar.tws.iter().enumerate()
+                .map(|(j, &tws)| {
+                    let expected = theoretical.get_speed(tws, twa);
+                    (self.is_reliable(i, j) && expected > 0.0).then(|| self.polar.speeds[i][j] / expected)
+                })
+                .collect())
+            .collect()
+    }
+
+    /// Overall measured / theoretical ratio, weighted by sample count
+    pub fn mean_performance(&self, theoretical: &PolarData) -> Option<f32> {
+        let (sum, weight) = self.performance(theoretical).iter().zip(&self.counts)
+            .flat_map(|(ratios, counts)| ratios.iter().zip(counts))
+            .filter_map(|(ratio, &count)| ratio.map(|r| (r * count as f32, count as f32)))
+            .fold((0.0, 0.0), |(sum, weight), (r, w)| (sum + r, weight + w));
+        (weight > 0.0).then(|| sum / weight)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn sample(second: i64, tws: f32, twa: f32, stw: f32) -> LogSample {
+        LogSample { time: Utc.with_ymd_and_hms(2025, 6, 21, 12, 0, 0).unwrap() + chrono::Duration::seconds(second), tws, twa, stw }
+    }
+
+    #[test]
+    fn test_fit_polar_from_log() {
+        let theoretical = PolarData {
+            tws: vec![10.0, 20.0],
+            twa: vec![60.0, 120.0],
+            speeds: vec![vec![8.0, 10.0], vec![10.0, 12.0]],
+            ..Default::default()
+        };
+
+        let mut samples = Vec::new();
+        // 60 s steady on starboard at TWA 120 in 10 kt, with one surf that stays within the steady spread
+        for s in 0..60 {
+            let stw = if s == 50 { 9.8 } else if s % 2 == 0 { 8.9 } else { 9.1 };
+            samples.push(sample(s, 10.0, 120.0, stw));
+        }
+        // Tack: TWA swings through the wind, nothing steady for the next 30 s
+        for s in 60..70 {
+            samples.push(sample(s, 10.0, 120.0 - 24.0 * (s - 60) as f32, 6.0));
+        }
+        for s in 70..80 {
+            samples.push(sample(s, 10.0, -120.0, 7.0));
+        }
+
+        let learner = PolarLearner::for_polar(&theoretical);
+        let learned = learner.fit(&samples);
+
+        // The first 24 s have no history, the tack is rejected, the spike is an outlier
+        assert_eq!(learned.steady_samples, 36);
+        assert_eq!(learned.counts[1][0], 35);
+        assert!((learned.polar.speeds[1][0] - 9.0).abs() < 0.01, "speed {}", learned.polar.speeds[1][0]);
+        assert_eq!(learned.counts[0], vec![0, 0]);
+
+        let ratio = learned.performance(&theoretical);
+        assert!((ratio[1][0].unwrap() - 0.9).abs() < 1e-3);
+        assert_eq!(ratio[0][0], None);
+        assert!((learned.mean_performance(&theoretical).unwrap() - 0.9).abs() < 1e-3);
+
+        // Unmeasured cells fall back to the theoretical polar
+        let merged = learned.merged_with(&theoretical);
+        assert!((merged.speeds[1][0] - 9.0).abs() < 0.01);
+        assert_eq!(merged.speeds[0][1], 10.0);
+    }
+
+    #[test]
+    fn test_merge_keeps_sail_set() {
+        let sail = |speeds: Vec<Vec<f32>>| PolarData { tws: vec![10.0, 20.0], twa: vec![60.0, 120.0], speeds, ..Default::default() };
+        let theoretical = PolarData::from_sails(vec![
+            ("Jib".to_string(), sail(vec![vec![8.0, 10.0], vec![7.0, 9.0]])),
+            ("Gennaker".to_string(), sail(vec![vec![6.0, 8.0], vec![10.0, 12.0]])),
+        ]);
+
+        // Only TWA 120 in 10 kt was measured, 10% slower than the polar
+        let learned = LearnedPolar {
+            polar: PolarData { tws: vec![10.0, 20.0], twa: vec![60.0, 120.0], speeds: vec![vec![0.0, 0.0], vec![9.0, 0.0]], ..Default::default() },
+            counts: vec![vec![0, 0], vec![35, 0]],
+            min_samples: 5,
+            total_samples: 35,
+            steady_samples: 35,
+        };
+        let merged = learned.merged_with(&theoretical);
+
+        assert_eq!(merged.sails, theoretical.sails);
+        let (best, speed) = merged.best_sail(10.0, 120.0).unwrap();
+        assert_eq!(best, 1);
+        assert!((speed - 9.0).abs() < 1e-4, "Measured cell sails at {}", speed);
+        assert!((merged.get_speed(10.0, 120.0) - 9.0).abs() < 1e-4);
+        assert_eq!(merged.best_sail(10.0, 60.0), Some((0, 8.0)));
+        assert_eq!(merged.crossover_chart(), theoretical.crossover_chart());
+    }
+}
@@ -1,12 +1,14 @@
-use crate::engine::models::{BoatState, Coordinate, WindData, CurrentData};
+use crate::engine::models::{BoatState, Coordinate, WindData, CurrentData, SeaState, Lineage, RouteLeg, Tack};
 use crate::engine::physics::PhysicsModel;
 use crate::engine::mask::LandMask;
+use crate::engine::projection::LocalProjection;
 use bevy::prelude::*;
 use rayon::prelude::*;
 use log::info;
 use geo_types_06 as gt06;
 use geo_booleanop::boolean::BooleanOp;
-use geo::{Point, ConvexHull, MultiPoint, MultiPolygon};
+use geo::{BoundingRect, Contains, Intersects, LineString, MultiPolygon, Point, Polygon, Rect};
+use std::collections::HashMap;
 
 #[derive(Resource)]
 pub struct RoutingState {
@@ -14,6 +16,12 @@ pub struct RoutingState {
     pub step_timer: Timer,
     pub router: IsochroneRouter,
     pub fronts: Vec<Vec<BoatState>>,
+    /// Set once the routing has terminated
+    pub outcome: Option<SolveOutcome>,
+    /// Route leg inspected in the UI
+    pub selected_leg: Option<usize>,
+    /// Draw the isochrones as filled bands on the map
+    pub show_bands: bool,
 }
 
 impl Default for RoutingState {
@@ -26,27 +34,380 @@ impl Default for RoutingState {
         // 1 hour time step
         let time_step = 3600.0;
         
-        let initial_state = BoatState {
-            position: start,
-            time: chrono::Utc::now(),
-            elapsed_time: 0.0,
-        };
+        let initial_state = BoatState::new(start, chrono::Utc::now());
 
         Self {
             is_playing: false,
             step_timer: Timer::from_seconds(0.5, TimerMode::Repeating),
             router: IsochroneRouter::new(start, destination, time_step),
             fronts: vec![vec![initial_state]],
+            outcome: None,
+            selected_leg: None,
+            show_bands: true,
         }
     }
 }
 
+impl RoutingState {
+    /// Reconstructs the best route found so far through the stored fronts
+    pub fn reconstruct_route(&self) -> Vec<RouteLeg> {
+        self.router.reconstruct_route(&self.fronts)
+    }
+
+    /// Area reached at every step so far; `isochrones()[k]` encloses `fronts[k + 1]`
+    pub fn isochrones(&self) -> &[Isochrone] {
+        &self.router.isochrones
+    }
+
+    /// Changes whenever the isochrones are extended or recomputed
+    pub fn isochrone_generation(&self) -> u64 {
+        self.router.isochrone_generation
+    }
+
+    /// Earliest time `coord` can be reached, if it is within the fronts computed so far
+    pub fn time_to_reach(&self, coord: &Coordinate) -> Option<chrono::DateTime<chrono::Utc>> {
+        let start = self.fronts.first()?.first()?;
+        self.router.time_to_reach(start, coord)
+    }
+
+    /// True if `coord` can be reached at or before `time`
+    pub fn is_reachable_by(&self, coord: &Coordinate, time: chrono::DateTime<chrono::Utc>) -> bool {
+        self.time_to_reach(coord).is_some_and(|earliest| earliest <= time)
+    }
+}
+
+/// Weather and sea conditions sampled by the router
+pub trait Environment: Sync {
+    /// Wind at a position and time, `None` outside the forecast
+    fn wind_at(&self, coord: &Coordinate, time: chrono::DateTime<chrono::Utc>) -> Option<WindData>;
+    fn current_at(&self, coord: &Coordinate, time: chrono::DateTime<chrono::Utc>) -> CurrentData;
+    fn sea_state_at(&self, coord: &Coordinate, time: chrono::DateTime<chrono::Utc>) -> Option<SeaState>;
+}
+
+/// `Environment` made of one closure per field
+pub struct Conditions<W, C, S> {
+    wind: W,
+    current: C,
+    sea_state: S,
+}
+
+impl<W, C, S> Conditions<W, C, S>
+where
+    W: Fn(&Coordinate, chrono::DateTime<chrono::Utc>) -> Option<WindData> + Sync,
+    C: Fn(&Coordinate, chrono::DateTime<chrono::Utc>) -> CurrentData + Sync,
+    S: Fn(&Coordinate, chrono::DateTime<chrono::Utc>) -> Option<SeaState> + Sync,
+{
+    pub fn new(wind: W, current: C, sea_state: S) -> Self {
+        Self { wind, current, sea_state }
+    }
+}
+
+impl<W, C, S> Environment for Conditions<W, C, S>
+where
+    W: Fn(&Coordinate, chrono::DateTime<chrono::Utc>) -> Option<WindData> + Sync,
+    C: Fn(&Coordinate, chrono::DateTime<chrono::Utc>) -> CurrentData + Sync,
+    S: Fn(&Coordinate, chrono::DateTime<chrono::Utc>) -> Option<SeaState> + Sync,
+{
+    fn wind_at(&self, coord: &Coordinate, time: chrono::DateTime<chrono::Utc>) -> Option<WindData> {
+        (self.wind)(coord, time)
+    }
+
+    fn current_at(&self, coord: &Coordinate, time: chrono::DateTime<chrono::Utc>) -> CurrentData {
+        (self.current)(coord, time)
+    }
+
+    fn sea_state_at(&self, coord: &Coordinate, time: chrono::DateTime<chrono::Utc>) -> Option<SeaState> {
+        (self.sea_state)(coord, time)
+    }
+}
+
+/// Spatial hash over the expansion candidates, used to give resampled front
+/// points the lineage of the nearest candidate that was actually sailed to.
+/// Candidates are indexed by their projected position (meters).
+struct CandidateIndex<'a> {
+    cell_size: f64,
+    cells: CandidateCells<'a>,
+    /// Populated cell range: min x, max x, min y, max y
+    bounds: Option<(i64, i64, i64, i64)>,
+}
+
+/// Projected candidates per grid cell
+type CandidateCells<'a> = HashMap<(i64, i64), Vec<((f64, f64), &'a BoatState)>>;
+
+impl<'a> CandidateIndex<'a> {
+    fn new(candidates: impl Iterator<Item = &'a BoatState>, projection: &LocalProjection, cell_size: f64) -> Self {
+        let mut cells = CandidateCells::new();
+        for candidate in candidates {
+            let point = projection.project(&candidate.position);
+            cells.entry(Self::cell_of(point, cell_size)).or_default().push((point, candidate));
+        }
+
+        let bounds = cells.keys().fold(None, |bounds: Option<(i64, i64, i64, i64)>, &(x, y)| {
+            Some(match bounds {
+                Some((min_x, max_x, min_y, max_y)) => (min_x.min(x), max_x.max(x), min_y.min(y), max_y.max(y)),
+                None => (x, x, y, y),
+            })
+        });
+
+        Self { cell_size, cells, bounds }
+    }
+
+    fn cell_of((x, y): (f64, f64), cell_size: f64) -> (i64, i64) {
+        ((x / cell_size).floor() as i64, (y / cell_size).floor() as i64)
+    }
+
+    /// Finds the candidate closest to the projected `point` and its distance (meters), searching
+    /// rings of cells outwards. Only `None` when there are no candidates at all.
+    fn nearest(&self, point: (f64, f64)) -> Option<(f64, &'a BoatState)> {
+        let (min_x, max_x, min_y, max_y) = self.bounds?;
+        let (cx, cy) = Self::cell_of(point, self.cell_size);
+        let mut best: Option<(f64, &'a BoatState)> = None;
+
+        // Ring that reaches the furthest populated cell
+        let max_ring = [cx - min_x, max_x - cx, cy - min_y, max_y - cy].into_iter().map(i64::abs).max().unwrap_or(0);
+        for ring in 0..=max_ring {
+            // Every point in a further ring is at least (ring - 1) cells away
+            if let Some((best_dist, _)) = best {
+                let min_dist = (ring - 1).max(0) as f64 * self.cell_size;
+                if min_dist * min_dist > best_dist { break; }
+            }
+
+            for x in (cx - ring)..=(cx + ring) {
+                for y in (cy - ring)..=(cy + ring) {
+                    if (x - cx).abs() != ring && (y - cy).abs() != ring { continue; }
+                    let Some(cell) = self.cells.get(&(x, y)) else { continue };
+                    for &((px, py), candidate) in cell {
+                        let dist_sq = (px - point.0).powi(2) + (py - point.1).powi(2);
+                        if best.is_none_or(|(best_dist, _)| dist_sq < best_dist) {
+                            best = Some((dist_sq, candidate));
+                        }
+                    }
+                }
+            }
+        }
+
+        best.map(|(dist_sq, candidate)| (dist_sq.sqrt(), candidate))
+    }
+}
+
+/// Why the routing stopped
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolveOutcome {
+    /// A leg entered the arrival radius. `arrival` is the interpolated arrival state.
+    Arrived { arrival: BoatState },
+    /// `max_duration` elapsed before reaching the destination
+    MaxDurationExceeded,
+    /// The front could not be expanded any further (no wind, land-locked...)
+    FrontCollapsed,
+    /// No point of the front has weather data anymore
+    OutOfWeatherData,
+}
+
+/// Area reachable at the end of a routing step
+#[derive(Debug, Clone)]
+pub struct Isochrone {
+    pub time: chrono::DateTime<chrono::Utc>,
+    pub elapsed_time: f64,
+    /// Lon/lat polygons enclosing the front. Longitudes are continuous around the front,
+    /// so an area crossing the anti-meridian may extend beyond ±180.
+    pub area: MultiPolygon<f64>,
+}
+
+impl Isochrone {
+    pub fn contains(&self, coord: &Coordinate) -> bool {
+        let Some(bounds) = self.area.bounding_rect() else { return false };
+        let coord = coord.unwrapped_around(bounds.center().x);
+        self.area.contains(&Point::new(coord.lon, coord.lat))
+    }
+
+    /// Distance from `coord` to the closest point of the isochrone outline (meters),
+    /// measured in a local projection centered on `coord`
+    pub fn distance_to_boundary(&self, coord: &Coordinate) -> f64 {
+        let projection = LocalProjection::new(*coord);
+        let to_local = |c: &geo::Coord<f64>| projection.project(&Coordinate::new(c.y, c.x));
+
+        self.area.0.iter()
+            .flat_map(|poly| poly.exterior().lines())
+            .map(|line| {
+                let (ax, ay) = to_local(&line.start);
+                let (bx, by) = to_local(&line.end);
+                let (dx, dy) = (bx - ax, by - ay);
+                let length_sq = dx * dx + dy * dy;
+                let t = if length_sq > 0.0 { (-(ax * dx + ay * dy) / length_sq).clamp(0.0, 1.0) } else { 0.0 };
+                (ax + t * dx).hypot(ay + t * dy)
+            })
+            .fold(f64::INFINITY, f64::min)
+    }
+}
+
+/// Result of a full routing run
+#[derive(Debug, Clone)]
+pub struct SolveResult {
+    pub outcome: SolveOutcome,
+    /// All computed fronts. On arrival the last front only holds the arrival state.
+    pub fronts: Vec<Vec<BoatState>>,
+    /// Best route, ending at the arrival point or at the closest point to the destination
+    pub route: Vec<RouteLeg>,
+    /// Area reached at every step
+    pub isochrones: Vec<Isochrone>,
+}
+
+/// Time lost for each manoeuvre (seconds). The boat is assumed stopped for that long,
+/// so the distance sailed in the step shrinks accordingly.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ManoeuvrePenalties {
+    pub tack: f64,
+    pub gybe: f64,
+    pub sail_change: f64,
+}
+
+impl Default for ManoeuvrePenalties {
+    fn default() -> Self {
+        Self {
+            tack: 120.0,
+            gybe: 180.0,
+            sail_change: 300.0,
+        }
+    }
+}
+
+impl ManoeuvrePenalties {
+    /// No penalty at all, every heading is immediately reachable
+    pub fn none() -> Self {
+        Self { tack: 0.0, gybe: 0.0, sail_change: 0.0 }
+    }
+
+    /// Time lost going from the parent leg to a new leg on `tack` at `twa` with `sail`
+    pub fn time_lost(&self, parent: Option<&Lineage>, tack: Tack, twa: f32, sail: Option<usize>) -> f64 {
+        let Some(parent) = parent else { return 0.0 };
+        let mut lost = 0.0;
+
+        if parent.tack != tack {
+            // Turning through the wind is a tack, through the stern a gybe
+            let mean_twa = (parent.twa.abs() + twa.abs()) / 2.0;
+            lost += if mean_twa < 90.0 { self.tack } else { self.gybe };
+        }
+        if matches!((parent.sail, sail), (Some(from), Some(to)) if from != to) {
+            lost += self.sail_change;
+        }
+        lost
+    }
+}
+
+/// Headings tried from every front point, centred on the direct bearing to the destination
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HeadingFan {
+    /// Number of headings per front point
+    pub count: usize,
+    /// The fan spans the direct bearing +- `half_width` degrees
+    pub half_width: f32,
+    /// Spend half of the headings around the optimal VMG angles and the direct bearing,
+    /// and use more headings where the front is sparse
+    pub adaptive: bool,
+}
+
+impl Default for HeadingFan {
+    fn default() -> Self {
+        Self { count: 360, half_width: 180.0, adaptive: false }
+    }
+}
+
+/// Bearing gap (degrees) past which a great-circle sector front is closed through the start
+const SECTOR_AREA_GAP: f32 = 10.0;
+
+/// Most headings a sparse front point can get, as a multiple of `HeadingFan::count`
+const MAX_FAN_DENSITY: f32 = 4.0;
+
+/// Length of the chords approximating a great-circle leg when checking it against land (meters)
+const LANDFALL_CHORD: f64 = 10_000.0;
+
+impl HeadingFan {
+    /// Coarse fan for ocean passages
+    pub fn ocean() -> Self {
+        Self { count: 72, half_width: 180.0, adaptive: true }
+    }
+
+    /// Fine fan for coastal racing
+    pub fn coastal() -> Self {
+        Self { count: 720, half_width: 180.0, adaptive: true }
+    }
+
+    /// Angle between two evenly spread headings (degrees)
+    pub fn step(&self) -> f32 {
+        let count = if self.adaptive { self.count / 2 } else { self.count };
+        (self.half_width * 2.0) / (count.max(2) as f32 - 1.0)
+    }
+
+    /// Headings (degrees, 0..360) to try around `direct_bearing`. In adaptive mode
+    /// `key_headings` get a cluster of fine headings each, and `density` (>= 1) scales
+    /// the number of headings of a point where the front is sparse.
+    pub fn headings(&self, direct_bearing: f32, key_headings: &[f32], density: f32) -> Vec<f32> {
+        let wrap = |heading: f32| heading.rem_euclid(360.0);
+        if !self.adaptive {
+            let step = self.step();
+            return (0..self.count.max(2)).map(|i| wrap(direct_bearing - self.half_width + i as f32 * step)).collect();
+        }
+
+        let count = (self.count as f32 * density.clamp(1.0, MAX_FAN_DENSITY)) as usize;
+        let even = (count / 2).max(2);
+        let step = (self.half_width * 2.0) / (even as f32 - 1.0);
+        let mut headings: Vec<f32> = (0..even).map(|i| wrap(direct_bearing - self.half_width + i as f32 * step)).collect();
+
+        // Fine clusters spanning one even step on each side of every key heading in the fan
+        let in_fan = |heading: &&f32| {
+            let offset = (*heading - direct_bearing + 180.0).rem_euclid(360.0) - 180.0;
+            offset.abs() <= self.half_width
+        };
+        let keys: Vec<f32> = key_headings.iter().filter(in_fan).copied().collect();
+        if !keys.is_empty() {
+            let per_key = count.saturating_sub(even) / keys.len();
+            let fine_step = 2.0 * step / (per_key + 1) as f32;
+            for key in keys {
+                headings.extend((1..=per_key).map(|i| wrap(key - step + i as f32 * fine_step)));
+            }
+        }
+
+        headings.sort_by(f32::total_cmp);
+        headings.dedup_by(|a, b| (*a - *b).abs() < 1e-3);
+        headings
+    }
+}
+
+/// How the candidates sailed from a front are reduced to the next front
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum PruningStrategy {
+    /// Union of the reachable polygons of every parent, resampled every `grid_precision`.
+    /// Follows the coast closely but is expensive with large fronts.
+    #[default]
+    PolygonUnion,
+    /// Sectors of `width` degrees of bearing from the start, keeping the candidate furthest
+    /// from the start in each
+    BearingSectors { width: f32 },
+    /// Bands of `width` meters of cross-track distance from the start -> destination great
+    /// circle, keeping the candidate furthest along it in each
+    GreatCircleSectors { width: f64 },
+}
+
 pub struct IsochroneRouter {
     pub start: Coordinate,
     pub destination: Coordinate,
     /// Time step in seconds
     pub time_step: f64, 
+    /// Front points per degree of latitude (111 km) along the resampled union outline
     pub grid_precision: f64,
+    /// Distance to the destination under which it is considered reached (meters)
+    pub arrival_radius: f64,
+    /// Maximum routing duration in seconds
+    pub max_duration: f64,
+    pub manoeuvre_penalties: ManoeuvrePenalties,
+    /// Only try headings between the optimal beat and run angles of the polar
+    pub vmg_mode: bool,
+    pub fan: HeadingFan,
+    pub pruning: PruningStrategy,
+    /// Area reached at the end of every step so far; `isochrones[k]` encloses `fronts[k + 1]`
+    pub isochrones: Vec<Isochrone>,
+    /// Incremented every time `isochrones` changes, so views can tell a recomputation
+    pub isochrone_generation: u64,
 }
 
 impl IsochroneRouter {
@@ -56,6 +417,14 @@ impl IsochroneRouter {
             destination, 
             time_step, 
             grid_precision: 400.0,
+            arrival_radius: 1852.0,
+            max_duration: 30.0 * 86400.0,
+            manoeuvre_penalties: ManoeuvrePenalties::default(),
+            vmg_mode: false,
+            fan: HeadingFan::default(),
+            pruning: PruningStrategy::default(),
+            isochrones: Vec::new(),
+            isochrone_generation: 0,
         }
     }
 
@@ -105,7 +474,93 @@ impl IsochroneRouter {
         Coordinate {
             lat: end_lat.to_degrees(),
             lon: end_lon.to_degrees(),
+        }.normalized()
+    }
+
+    /// Spacing between each front point and its neighbours relative to the median spacing,
+    /// at least 1. Front points are ordered along the front.
+    fn front_sparsity(front: &[BoatState]) -> Vec<f32> {
+        if front.len() < 3 {
+            return vec![1.0; front.len()];
+        }
+        let gaps: Vec<f64> = front.windows(2).map(|pair| Self::calculate_distance(&pair[0].position, &pair[1].position)).collect();
+        let spacing: Vec<f64> = (0..front.len())
+            .map(|i| match (i.checked_sub(1).map(|j| gaps[j]), gaps.get(i)) {
+                (Some(before), Some(&after)) => before.min(after),
+                (Some(gap), None) | (None, Some(&gap)) => gap,
+                (None, None) => 0.0,
+            })
+            .collect();
+
+        let mut sorted = spacing.clone();
+        sorted.sort_by(f64::total_cmp);
+        let median = sorted[sorted.len() / 2].max(1.0);
+        spacing.iter().map(|&gap| (gap / median).max(1.0) as f32).collect()
+    }
+
+    /// Optimal beat and run TWA, `None` in calm air where every angle has zero VMG
+    fn vmg_angles(polar: &crate::parsers::polars::PolarData, tws_kts: f32) -> Option<(f32, f32)> {
+        let (beat, beat_vmg) = polar.optimal_beat(tws_kts);
+        (beat_vmg > 0.0).then(|| (beat, polar.optimal_run(tws_kts).0))
+    }
+
+    /// Fraction of the great-circle leg `from -> to` at which it first enters a land cell,
+    /// or `None` if the whole leg is over water. The leg is split into short chords that
+    /// are rasterised cell by cell against the land mask.
+    fn landfall_fraction(from: &Coordinate, to: &Coordinate, land_mask: &LandMask) -> Option<f64> {
+        let distance = Self::calculate_distance(from, to);
+        let bearing = Self::calculate_bearing(from, to);
+        let chords = (distance / LANDFALL_CHORD).ceil().max(1.0) as usize;
+
+        let mut chord_start = *from;
+        for i in 1..=chords {
+            let chord_end = if i == chords { *to } else { Self::calculate_destination(from, distance * i as f64 / chords as f64, bearing) };
+            if let Some(t) = land_mask.first_land_along(&chord_start, &chord_end) {
+                return Some(((i - 1) as f64 + t) / chords as f64);
+            }
+            chord_start = chord_end;
+        }
+        None
+    }
+
+    /// Polygon of the points reachable from `parent`: the outline sorted by bearing around
+    /// the parent. Where the fan has a gap (no-go zone, VMG mode) the parent closes it.
+    /// All points are in the projected frame (meters).
+    fn fan_polygon(parent: (f64, f64), outline: &[(f64, f64)], angle_step: f32) -> Option<gt06::Polygon<f64>> {
+        let mut points: Vec<(f64, gt06::Coordinate<f64>)> = outline.iter()
+            .map(|&(x, y)| {
+                let angle = (x - parent.0).atan2(y - parent.1);
+                // Round coordinates to mitigate floating point artifacts in Martinez-Rueda
+                (angle, gt06::Coordinate { x: (x * 1e3).round() / 1e3, y: (y * 1e3).round() / 1e3 })
+            })
+            .collect();
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        points.dedup_by(|a, b| (a.1.x - b.1.x).abs() < 1e-6 && (a.1.y - b.1.y).abs() < 1e-6);
+        if points.len() < 2 {
+            return None;
+        }
+
+        // Start the ring right after the largest angular gap, and close that gap through
+        // the parent when it is wider than the fan step
+        let gap = |i: usize| {
+            let next = points[(i + 1) % points.len()].0;
+            let gap = next - points[i].0;
+            if gap <= 0.0 { gap + std::f64::consts::TAU } else { gap }
+        };
+        let widest = (0..points.len()).max_by(|&a, &b| gap(a).total_cmp(&gap(b)))?;
+        let open = gap(widest) > 1.5 * (angle_step as f64).to_radians();
+        let first = (widest + 1) % points.len();
+        points.rotate_left(first);
+
+        let mut ring: Vec<gt06::Coordinate<f64>> = points.into_iter().map(|(_, c)| c).collect();
+        if open {
+            ring.push(gt06::Coordinate { x: parent.0, y: parent.1 });
+        }
+        if ring.len() < 3 {
+            return None;
         }
+        ring.push(ring[0]);
+        Some(gt06::Polygon::new(gt06::LineString(ring), vec![]))
     }
 
     /// Performs one step of the isochrone expansion
@@ -115,82 +570,165 @@ impl IsochroneRouter {
         physics: &PhysicsModel,
         polar: &crate::parsers::polars::PolarData,
         land_mask: &LandMask,
-        wind_at: impl Fn(&Coordinate) -> WindData + Sync, // Placeholder for data grid lookup
-        current_at: impl Fn(&Coordinate) -> CurrentData + Sync, // Placeholder for data grid lookup
+        environment: &impl Environment,
     ) -> Vec<BoatState> {
         info!("Expanding isochrone front for {} points", current_front.len());
+        if current_front.is_empty() {
+            return Vec::new();
+        }
         
-        // Define the search fan (widened to allow for tacking/wearing)
-        let num_headings = 360;
-        let max_angle = 180.0; // Sweep from -180 to +180 degrees
-        let angle_step = (max_angle * 2.0) / (num_headings as f32 - 1.0);
+        let angle_step = self.fan.step();
+        let density = if self.fan.adaptive { Self::front_sparsity(current_front) } else { vec![1.0; current_front.len()] };
 
-        let expansion_fans: Vec<Vec<BoatState>> = current_front.par_iter().map(|state| {
+        // Each fan yields the sailed candidates and the reachable outline around the parent:
+        // legs that end on land are cut at the coast so the outline follows it
+        let (expansion_fans, outlines): (Vec<Vec<BoatState>>, Vec<Vec<Coordinate>>) = current_front.par_iter().enumerate().map(|(parent_index, state)| {
             let direct_bearing = Self::calculate_bearing(&state.position, &self.destination);
-            let mut local_candidates = Vec::with_capacity(num_headings);
 
-            for i in 0..num_headings {
-                let offset = -max_angle + (i as f32 * angle_step);
-                let mut test_heading = direct_bearing + offset;
-                
-                if test_heading < 0.0 { test_heading += 360.0; }
-                if test_heading >= 360.0 { test_heading -= 360.0; }
+            // Outside the forecast the boat drifts with the current only
+            let wind = environment.wind_at(&state.position, state.time).unwrap_or(WindData { u: 0.0, v: 0.0 });
+            let current = environment.current_at(&state.position, state.time);
+            let sea_state = environment.sea_state_at(&state.position, state.time);
+
+            let vmg_angles = if self.vmg_mode || self.fan.adaptive { Self::vmg_angles(polar, wind.speed() * 1.94384) } else { None };
+
+            // Pinching above the beat angle or running deeper than the run angle is
+            // always beaten by the optimal angles, so skip those headings
+            let twa_range = match vmg_angles {
+                Some((beat, run)) if self.vmg_mode => (beat - 1.0)..=(run + 1.0),
+                _ => 0.0..=180.0,
+            };
+
+            // Adaptive fans refine around the direct bearing and both tacks of the VMG angles
+            let twd = wind.direction();
+            let key_headings = match vmg_angles {
+                Some((beat, run)) => vec![direct_bearing, twd - beat, twd + beat, twd - run, twd + run],
+                None => Vec::new(),
+            };
+            let headings = self.fan.headings(direct_bearing, &key_headings, density[parent_index]);
+            let mut local_candidates = Vec::with_capacity(headings.len());
+            let mut outline = Vec::with_capacity(headings.len());
+
+            for test_heading in headings {
+                if !twa_range.contains(&PhysicsModel::calculate_twa(wind.direction(), test_heading)) {
+                    continue;
+                }
 
-                let wind = wind_at(&state.position);
-                let current = current_at(&state.position);
-                let (sog, cog) = physics.compute_vector(test_heading, &wind, &current, polar, None);
+                let vector = physics.compute_vector(test_heading, state, &wind, &current, polar, sea_state.as_ref());
+                let (sog, cog, tack, twa, sail) = (vector.sog, vector.cog, vector.tack, vector.twa.abs(), vector.sail);
 
                 if sog <= 0.001 { continue; }
 
-                let distance_m = (sog as f64) * self.time_step;
+                let time_lost = self.manoeuvre_penalties.time_lost(state.lineage.as_ref(), tack, twa, sail);
+                let distance_m = (sog as f64) * (self.time_step - time_lost).max(0.0);
                 let new_position = Self::calculate_destination(&state.position, distance_m, cog);
 
-                if !land_mask.is_land(&new_position) {
+                // A leg that runs into land anywhere (not only at its end) stops a meter short of the coast
+                if let Some(t) = Self::landfall_fraction(&state.position, &new_position, land_mask) {
+                    outline.push(Self::calculate_destination(&state.position, (t * distance_m - 1.0).max(0.0), cog));
+                } else {
+                    outline.push(new_position);
                     local_candidates.push(BoatState {
                         position: new_position,
                         time: state.time + chrono::Duration::seconds(self.time_step as i64),
                         elapsed_time: state.elapsed_time + self.time_step,
+                        lineage: Some(Lineage {
+                            parent_index,
+                            heading: test_heading,
+                            tws: wind.speed(),
+                            twa,
+                            tack,
+                            sog,
+                            cog,
+                            sail,
+                        }),
                     });
                 }
             }
-            local_candidates
-        }).collect();
-
-        // --- Pass 1: Convert Fans to Convex Hull Polygons ---
-        let mut polygons: Vec<gt06::Polygon<f64>> = current_front.iter().zip(expansion_fans.iter())
-            .filter(|(_, fan)| fan.len() >= 2) 
-            .map(|(parent, fan)| {
-                // Collect points including parent
-                let mut points = Vec::with_capacity(fan.len() + 1);
-                points.push(Point::new(parent.position.lon, parent.position.lat));
-                for candidate in fan {
-                    points.push(Point::new(candidate.position.lon, candidate.position.lat));
-                }
-                
-                // Compute Convex Hull using modern geo crate
-                let mp = MultiPoint::new(points);
-                let hull = mp.convex_hull();
-                
-                // Convert back-conversion to legacy gt06 types for Martinez-Rueda
-                // Round coordinates to mitigate floating point artifacts and dedupe
-                let hull_exterior = hull.exterior();
-                let mut gt06_coords: Vec<gt06::Coordinate<f64>> = hull_exterior.0.iter()
-                    .map(|c| {
-                        let x = (c.x * 1e7).round() / 1e7;
-                        let y = (c.y * 1e7).round() / 1e7;
-                        gt06::Coordinate { x, y }
-                    })
-                    .collect();
-                
-                // Deduplicate consecutive points to remove zero-length segments
-                gt06_coords.dedup_by(|a, b| (a.x - b.x).abs() < 1e-9 && (a.y - b.y).abs() < 1e-9);
+            (local_candidates, outline)
+        }).unzip();
 
-                gt06::Polygon::new(gt06::LineString(gt06_coords), vec![])
+        let (mut next_front, area) = match self.pruning {
+            PruningStrategy::PolygonUnion => self.union_front(current_front, &expansion_fans, &outlines, angle_step, land_mask),
+            PruningStrategy::BearingSectors { width } => {
+                let width = width.max(1e-3);
+                let front = Self::sector_front(expansion_fans, |position| {
+                    let bearing = Self::calculate_bearing(&self.start, position).rem_euclid(360.0) as f64;
+                    ((bearing / width as f64).floor() as i64, Self::calculate_distance(&self.start, position))
+                });
+                let area = self.sector_area(&front, width);
+                (front, area)
+            }
+            PruningStrategy::GreatCircleSectors { width } => {
+                let width = width.max(1.0);
+                let front = Self::sector_front(expansion_fans, |position| {
+                    let (cross_track, along_track) = self.track_distances(position);
+                    ((cross_track / width).floor() as i64, along_track)
+                });
+                let area = self.sector_area(&front, SECTOR_AREA_GAP);
+                (front, area)
+            }
+        };
+
+        // --- Pass 4: No Backtracking ---
+        // Every point must get farther from the start or closer to the destination than
+        // its own parent, and must not fall back into an area reached at an earlier step
+        next_front.retain(|state| {
+            let Some(parent) = state.lineage.and_then(|lineage| current_front.get(lineage.parent_index)) else { return false };
+            let away_from_start = Self::calculate_distance(&self.start, &state.position) > Self::calculate_distance(&self.start, &parent.position);
+            let towards_destination = Self::calculate_distance(&state.position, &self.destination) < Self::calculate_distance(&parent.position, &self.destination);
+            away_from_start || towards_destination
+        });
+        let earlier: Vec<(Rect<f64>, &MultiPolygon<f64>)> = self.isochrones.iter()
+            .filter_map(|isochrone| isochrone.area.bounding_rect().map(|rect| (rect, &isochrone.area)))
+            .collect();
+        let inside = |state: &BoatState, (rect, area): &(Rect<f64>, &MultiPolygon<f64>)| {
+            let position = state.position.unwrapped_around(rect.center().x);
+            let point = Point::new(position.lon, position.lat);
+            rect.intersects(&point) && area.contains(&point)
+        };
+        next_front = next_front.into_par_iter()
+            .filter(|state| !earlier.iter().any(|isochrone| inside(state, isochrone)))
+            .collect();
+        self.isochrone_generation += 1;
+        self.isochrones.push(Isochrone {
+            time: current_front[0].time + chrono::Duration::seconds(self.time_step as i64),
+            elapsed_time: current_front[0].elapsed_time + self.time_step,
+            area,
+        });
+
+        info!("Pruning resulted in {} frontier points", next_front.len());
+        
+        next_front
+    }
+
+    /// Polygon union pruning: unions the reachable polygon of every parent and resamples
+    /// the exterior of the union. The geometry is built in a local projection centered on
+    /// the front, so it is metric at any latitude and continuous across the anti-meridian.
+    fn union_front(
+        &self,
+        current_front: &[BoatState],
+        expansion_fans: &[Vec<BoatState>],
+        outlines: &[Vec<Coordinate>],
+        angle_step: f32,
+        land_mask: &LandMask,
+    ) -> (Vec<BoatState>, MultiPolygon<f64>) {
+        // --- Pass 1: Convert Fans to Star-Shaped Polygons ---
+        // Unlike a convex hull, the outline keeps the concavities cut by the coast,
+        // so bays and the lee of headlands are not considered reached
+        let Some(projection) = LocalProjection::centered_on(current_front.iter().map(|state| &state.position)) else {
+            return (Vec::new(), MultiPolygon(Vec::new()));
+        };
+        let polygons: Vec<gt06::Polygon<f64>> = current_front.iter().zip(expansion_fans).zip(outlines)
+            .filter(|((_, fan), _)| fan.len() >= 2)
+            .filter_map(|((parent, _), outline)| {
+                let outline: Vec<(f64, f64)> = outline.iter().map(|c| projection.project(c)).collect();
+                Self::fan_polygon(projection.project(&parent.position), &outline, angle_step)
             })
             .collect();
 
         if polygons.is_empty() {
-            return Vec::new();
+            return (Vec::new(), MultiPolygon(Vec::new()));
         }
 
         // --- Pass 2: Geometric Union (Polygon Clipping) ---
@@ -201,7 +739,7 @@ impl IsochroneRouter {
             .collect();
 
         if multi_polygons.is_empty() {
-            return Vec::new();
+            return (Vec::new(), MultiPolygon(Vec::new()));
         }
 
         let mut iteration = 0;
@@ -224,57 +762,362 @@ impl IsochroneRouter {
         }
 
         // --- Pass 3: Extract Exterior Points ---
+        // Resampled points inherit the lineage of the nearest sailed candidate
+        let candidate_index = CandidateIndex::new(expansion_fans.iter().flatten(), &projection, 5000.0);
         let final_multi = multi_polygons.pop().unwrap();
         let mut next_front = Vec::new();
         let front_time = current_front[0].time + chrono::Duration::seconds(self.time_step as i64);
         let elapsed = current_front[0].elapsed_time + self.time_step;
 
-        for poly in final_multi.0 {
-            let exterior = poly.exterior();
+        let spacing = 111_000.0 / self.grid_precision;
+
+        let mut area = MultiPolygon(Vec::new());
+        for poly in &final_multi.0 {
             // Resample the exterior to maintain point density
-            // We'll take points roughly at the grid_precision resolution
-            let coords = &exterior.0;
+            let coords = &poly.exterior().0;
             if coords.len() < 2 { continue; }
 
-            for i in 0..coords.len()-1 {
-                let p1 = coords[i];
-                let p2 = coords[i+1];
-                let c1 = Coordinate::new(p1.y, p1.x);
-                let c2 = Coordinate::new(p2.y, p2.x);
-                let dist = Self::calculate_distance(&c1, &c2);
-                
-                // Resample along the segment
-                let steps = (dist * self.grid_precision / 111000.0).max(1.0) as usize;
+            let mut samples: Vec<((f64, f64), Coordinate, Option<BoatState>)> = Vec::new();
+            for segment in coords.windows(2) {
+                let (p1, p2) = (segment[0], segment[1]);
+                let steps = ((p2.x - p1.x).hypot(p2.y - p1.y) / spacing).max(1.0) as usize;
                 for s in 0..steps {
                     let t = s as f64 / steps as f64;
-                    let interp_lon = p1.x + t * (p2.x - p1.x);
-                    let interp_lat = p1.y + t * (p2.y - p1.y);
-                    
-                    let pos = Coordinate::new(interp_lat, interp_lon);
-                    
-                    // Filter: Must not be on land, and must not be a redundant point at the start
-                    if !land_mask.is_land(&pos) {
-                        next_front.push(BoatState {
-                            position: pos,
-                            time: front_time,
-                            elapsed_time: elapsed,
-                        });
-                    }
+                    let point = (p1.x + t * (p2.x - p1.x), p1.y + t * (p2.y - p1.y));
+                    let pos = projection.unproject(point.0, point.1).normalized();
+
+                    // Filter: must not be on land, and the leg from the parent it inherits
+                    // must not cross land either. Legs to the candidates themselves were
+                    // already checked when expanding the fan.
+                    let nearest = candidate_index.nearest(point);
+                    let lineage = nearest.and_then(|(_, c)| c.lineage);
+                    debug_assert!(lineage.is_some(), "Front point {:?} has no sailed candidate to inherit from", pos);
+                    let sailed = nearest.is_some_and(|(distance, _)| distance < 1.0);
+                    let parent = lineage.and_then(|lineage| current_front.get(lineage.parent_index));
+                    let reached = sailed || (!land_mask.is_land(&pos)
+                        && parent.is_none_or(|parent| Self::landfall_fraction(&parent.position, &pos, land_mask).is_none()));
+                    let state = reached.then_some(BoatState {
+                        position: pos,
+                        time: front_time,
+                        elapsed_time: elapsed,
+                        lineage,
+                    });
+                    samples.push((point, pos, state));
+                }
+            }
+            next_front.extend(samples.iter().filter_map(|(_, _, state)| state.clone()));
+
+            // Outline edges that enter land are cut at the coast. Without any cut the reached
+            // area is the exterior ring; otherwise each stretch of open water is closed back
+            // through the parents of its ends, so the area never bridges land.
+            let count = samples.len();
+            let next = |i: usize| (i + 1) % count;
+            // Fraction of each edge, from a reached sample to the next one, where it enters land
+            let edge_land: Vec<Option<f64>> = (0..count)
+                .map(|i| samples[i].2.as_ref().and_then(|_| land_mask.first_land_along(&samples[i].1, &samples[next(i)].1)))
+                .collect();
+            let cuts: Vec<bool> = (0..count)
+                .map(|i| samples[i].2.is_none() || samples[next(i)].2.is_none() || edge_land[i].is_some())
+                .collect();
+            let Some(first_cut) = cuts.iter().position(|&cut| cut) else {
+                area.0.push(Self::to_geo_polygon(poly.exterior(), &projection));
+                continue;
+            };
+
+            // Point at fraction `t` of the edge from sample `a` towards sample `b`, just short of the coast
+            let coast = |a: usize, b: usize, t: f64| {
+                let (from, to) = (samples[a].0, samples[b].0);
+                let t = (t - 1e-3).max(0.0);
+                gt06::Coordinate { x: from.0 + t * (to.0 - from.0), y: from.1 + t * (to.1 - from.1) }
+            };
+            let parent_of = |i: usize| {
+                samples[i].2.as_ref()
+                    .and_then(|state| state.lineage)
+                    .and_then(|lineage| current_front.get(lineage.parent_index))
+                    .map(|parent| {
+                        let (x, y) = projection.project(&parent.position);
+                        gt06::Coordinate { x, y }
+                    })
+            };
+
+            let mut run: Vec<usize> = Vec::new();
+            for offset in 1..=count {
+                let i = (first_cut + offset) % count;
+                if samples[i].2.is_some() {
+                    run.push(i);
+                }
+                if !cuts[i] || run.is_empty() {
+                    continue;
+                }
+
+                let (first, last) = (run[0], run[run.len() - 1]);
+                let previous = (first + count - 1) % count;
+                let mut ring: Vec<gt06::Coordinate<f64>> = Vec::with_capacity(run.len() + 4);
+                ring.extend(land_mask.first_land_along(&samples[first].1, &samples[previous].1).map(|t| coast(first, previous, t)));
+                ring.extend(run.iter().map(|&j| gt06::Coordinate { x: samples[j].0.0, y: samples[j].0.1 }));
+                ring.extend(edge_land[last].map(|t| coast(last, next(last), t)));
+                ring.extend(parent_of(last));
+                ring.extend(parent_of(first).filter(|parent| Some(*parent) != parent_of(last)));
+                run.clear();
+
+                if ring.len() >= 3 {
+                    ring.push(ring[0]);
+                    area.0.push(Self::to_geo_polygon(&gt06::LineString(ring), &projection));
                 }
             }
         }
 
-        // --- Pass 4: Final Novelty Pruning (Avoid returning to start/center) ---
-        // Only keep points that are actually moving away from their lineage
-        let start_pos = current_front[0].position; // Rough approximation for test compatibility
-        next_front.retain(|state| {
-            let d = Self::calculate_distance(&state.position, &start_pos);
-            d > 100.0 // At least 10 meters away from start
-        });
+        (next_front, area)
+    }
 
-        info!("Polygon clipping resulted in {} frontier points", next_front.len());
-        
-        next_front
+    /// Lon/lat polygon of a projected ring
+    fn to_geo_polygon(ring: &gt06::LineString<f64>, projection: &LocalProjection) -> Polygon<f64> {
+        let coords: Vec<(f64, f64)> = ring.0.iter()
+            .map(|c| projection.unproject(c.x, c.y))
+            .map(|coord| (coord.lon, coord.lat))
+            .collect();
+        Polygon::new(LineString::from(coords), vec![])
+    }
+
+    /// Sector pruning: keeps the candidate with the highest score in each sector.
+    /// `sector_of` returns the sector key and score of a position.
+    fn sector_front(expansion_fans: Vec<Vec<BoatState>>, sector_of: impl Fn(&Coordinate) -> (i64, f64)) -> Vec<BoatState> {
+        let mut sectors: HashMap<i64, (f64, BoatState)> = HashMap::new();
+        for candidate in expansion_fans.into_iter().flatten() {
+            let (key, score) = sector_of(&candidate.position);
+            match sectors.get(&key) {
+                Some((best, _)) if *best >= score => {}
+                _ => { sectors.insert(key, (score, candidate)); }
+            }
+        }
+
+        let mut keys: Vec<i64> = sectors.keys().copied().collect();
+        keys.sort_unstable();
+        keys.into_iter().filter_map(|key| sectors.remove(&key)).map(|(_, state)| state).collect()
+    }
+
+    /// Area enclosed by a sector-pruned front, seen as a star around the start
+    fn sector_area(&self, front: &[BoatState], angle_step: f32) -> MultiPolygon<f64> {
+        let projection = LocalProjection::new(self.start);
+        let positions: Vec<(f64, f64)> = front.iter().map(|state| projection.project(&state.position)).collect();
+        let polygons = Self::fan_polygon((0.0, 0.0), &positions, angle_step)
+            .map(|poly| Self::to_geo_polygon(poly.exterior(), &projection));
+        MultiPolygon(polygons.into_iter().collect())
+    }
+
+    /// Signed cross-track distance (positive to the right) and along-track distance of
+    /// `position` relative to the start -> destination great circle, in meters
+    pub fn track_distances(&self, position: &Coordinate) -> (f64, f64) {
+        let r_earth = 6_371_000.0;
+        let angular_dist = Self::calculate_distance(&self.start, position) / r_earth;
+        let relative_bearing = (Self::calculate_bearing(&self.start, position) - Self::calculate_bearing(&self.start, &self.destination)) as f64;
+        let relative_bearing = relative_bearing.to_radians();
+
+        let cross_track = (angular_dist.sin() * relative_bearing.sin()).asin();
+        let along_track = (angular_dist.cos() / cross_track.cos()).clamp(-1.0, 1.0).acos() * relative_bearing.cos().signum();
+        (cross_track * r_earth, along_track * r_earth)
+    }
+
+    /// Earliest time `coord` can be reached from `start` with the isochrones computed so
+    /// far, interpolated between the two isochrones around it. `None` if not reached yet.
+    pub fn time_to_reach(&self, start: &BoatState, coord: &Coordinate) -> Option<chrono::DateTime<chrono::Utc>> {
+        let k = self.isochrones.iter().position(|isochrone| isochrone.contains(coord))?;
+        let isochrone = &self.isochrones[k];
+        let (previous_time, previous_distance) = match k.checked_sub(1).map(|j| &self.isochrones[j]) {
+            Some(previous) => (previous.time, previous.distance_to_boundary(coord)),
+            None => (start.time, Self::calculate_distance(&start.position, coord)),
+        };
+
+        let next_distance = isochrone.distance_to_boundary(coord);
+        let fraction = previous_distance / (previous_distance + next_distance).max(1e-9);
+        let span = (isochrone.time - previous_time).num_milliseconds() as f64;
+        Some(previous_time + chrono::Duration::milliseconds((span * fraction) as i64))
+    }
+
+    /// Runs the isochrone expansion from `initial_state` until the destination is reached
+    /// or the routing cannot continue.
+    pub fn solve(
+        &mut self,
+        initial_state: BoatState,
+        physics: &PhysicsModel,
+        polar: &crate::parsers::polars::PolarData,
+        land_mask: &LandMask,
+        environment: &impl Environment,
+    ) -> SolveResult {
+        let mut fronts = vec![vec![initial_state]];
+
+        let outcome = loop {
+            if let Some(outcome) = self.advance(&mut fronts, physics, polar, land_mask, environment) {
+                break outcome;
+            }
+        };
+
+        info!("Routing finished after {} steps: {:?}", fronts.len() - 1, outcome);
+        let route = self.reconstruct_route(&fronts);
+
+        SolveResult { outcome, fronts, route, isochrones: self.isochrones.clone() }
+    }
+
+    /// Expands the last front of `fronts` by one step and checks for termination.
+    /// Returns `Some` with the outcome once the routing is finished; on arrival the
+    /// new front is replaced by the interpolated arrival state.
+    pub fn advance(
+        &mut self,
+        fronts: &mut Vec<Vec<BoatState>>,
+        physics: &PhysicsModel,
+        polar: &crate::parsers::polars::PolarData,
+        land_mask: &LandMask,
+        environment: &impl Environment,
+    ) -> Option<SolveOutcome> {
+        let current_front = match fronts.last() {
+            Some(front) if !front.is_empty() => front,
+            _ => return Some(SolveOutcome::FrontCollapsed),
+        };
+
+        if current_front[0].elapsed_time >= self.max_duration {
+            return Some(SolveOutcome::MaxDurationExceeded);
+        }
+
+        if current_front.iter().all(|state| environment.wind_at(&state.position, state.time).is_none()) {
+            return Some(SolveOutcome::OutOfWeatherData);
+        }
+
+        // Restarting from an earlier front (or a new routing) forgets the later isochrones
+        if self.isochrones.len() >= fronts.len() {
+            self.isochrones.truncate(fronts.len() - 1);
+            self.isochrone_generation += 1;
+        }
+
+        let next_front = self.step(current_front, physics, polar, land_mask, environment);
+
+        if next_front.is_empty() {
+            return Some(SolveOutcome::FrontCollapsed);
+        }
+
+        if let Some(arrival) = self.detect_arrival(current_front, &next_front) {
+            fronts.push(vec![arrival.clone()]);
+            return Some(SolveOutcome::Arrived { arrival });
+        }
+
+        fronts.push(next_front);
+        None
+    }
+
+    /// Looks for the earliest leg between `parent_front` and `next_front` that enters
+    /// the arrival radius, and interpolates the arrival state along it.
+    pub fn detect_arrival(&self, parent_front: &[BoatState], next_front: &[BoatState]) -> Option<BoatState> {
+        let mut best: Option<(f64, &BoatState, &BoatState)> = None;
+
+        for state in next_front {
+            let Some(lineage) = state.lineage else { continue };
+            let Some(parent) = parent_front.get(lineage.parent_index) else { continue };
+            if let Some(t) = self.arrival_fraction(&parent.position, &state.position)
+                && best.is_none_or(|(best_t, _, _)| t < best_t)
+            {
+                best = Some((t, parent, state));
+            }
+        }
+
+        let (t, parent, state) = best?;
+        let leg_duration = state.elapsed_time - parent.elapsed_time;
+        let position = Coordinate::new(
+            parent.position.lat + t * (state.position.lat - parent.position.lat),
+            parent.position.lon + t * Coordinate::normalize_lon(state.position.lon - parent.position.lon),
+        ).normalized();
+
+        Some(BoatState {
+            position,
+            time: parent.time + chrono::Duration::milliseconds((t * leg_duration * 1000.0) as i64),
+            elapsed_time: parent.elapsed_time + t * leg_duration,
+            lineage: state.lineage,
+        })
+    }
+
+    /// Fraction of the segment `from -> to` at which it first enters the arrival radius,
+    /// computed in a local flat projection centered on the destination.
+    fn arrival_fraction(&self, from: &Coordinate, to: &Coordinate) -> Option<f64> {
+        let r_earth = 6_371_000.0;
+        let cos_lat = self.destination.lat.to_radians().cos();
+        let project = |c: &Coordinate| {
+            (
+                Coordinate::normalize_lon(c.lon - self.destination.lon).to_radians() * cos_lat * r_earth,
+                (c.lat - self.destination.lat).to_radians() * r_earth,
+            )
+        };
+
+        let (x0, y0) = project(from);
+        let (x1, y1) = project(to);
+        let r2 = self.arrival_radius * self.arrival_radius;
+
+        if x0 * x0 + y0 * y0 <= r2 {
+            return Some(0.0);
+        }
+
+        // Solve |p0 + t (p1 - p0)|^2 = r^2 for the smallest t in [0, 1]
+        let (dx, dy) = (x1 - x0, y1 - y0);
+        let a = dx * dx + dy * dy;
+        if a == 0.0 { return None; }
+        let b = 2.0 * (x0 * dx + y0 * dy);
+        let c = x0 * x0 + y0 * y0 - r2;
+        let discriminant = b * b - 4.0 * a * c;
+        if discriminant < 0.0 { return None; }
+
+        let t = (-b - discriminant.sqrt()) / (2.0 * a);
+        (0.0..=1.0).contains(&t).then_some(t)
+    }
+
+    /// Finds the point of the last non-empty front closest to the destination.
+    /// Returns `(front_index, point_index)`.
+    pub fn best_arrival(&self, fronts: &[Vec<BoatState>]) -> Option<(usize, usize)> {
+        let (front_idx, front) = fronts.iter().enumerate().rev().find(|(_, f)| !f.is_empty())?;
+        let point_idx = front.iter()
+            .enumerate()
+            .map(|(i, state)| (i, Self::calculate_distance(&state.position, &self.destination)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i)?;
+        Some((front_idx, point_idx))
+    }
+
+    /// Walks the lineage back from the best arrival point and returns the ordered route legs
+    pub fn reconstruct_route(&self, fronts: &[Vec<BoatState>]) -> Vec<RouteLeg> {
+        match self.best_arrival(fronts) {
+            Some((front_idx, point_idx)) => Self::reconstruct_route_from(fronts, front_idx, point_idx),
+            None => Vec::new(),
+        }
+    }
+
+    /// Walks the lineage back from `fronts[front_idx][point_idx]` to the departure point
+    pub fn reconstruct_route_from(fronts: &[Vec<BoatState>], front_idx: usize, point_idx: usize) -> Vec<RouteLeg> {
+        let mut legs = Vec::with_capacity(front_idx);
+        let Some(mut state) = fronts.get(front_idx).and_then(|f| f.get(point_idx)) else {
+            return legs;
+        };
+
+        for idx in (1..=front_idx).rev() {
+            let Some(lineage) = state.lineage else { break };
+            let Some(parent) = fronts[idx - 1].get(lineage.parent_index) else {
+                log::warn!("Broken lineage at front {}: parent {} out of range", idx, lineage.parent_index);
+                break;
+            };
+
+            legs.push(RouteLeg {
+                from: parent.position,
+                to: state.position,
+                start_time: parent.time,
+                end_time: state.time,
+                heading: lineage.heading,
+                tws: lineage.tws,
+                twa: lineage.twa,
+                tack: lineage.tack,
+                sog: lineage.sog,
+                cog: lineage.cog,
+                sail: lineage.sail,
+            });
+            state = parent;
+        }
+
+        legs.reverse();
+        legs
     }
 }
 
@@ -284,6 +1127,30 @@ mod tests {
     use crate::engine::models::WindData;
     use crate::parsers::polars::PolarData;
 
+    /// Polar sailing at `speed` knots at every angle and wind speed
+    fn constant_polar(speed: f32) -> PolarData {
+        PolarData {
+            tws: vec![0.0, 20.0],
+            twa: vec![0.0, 180.0],
+            speeds: vec![vec![speed, speed], vec![speed, speed]],
+            ..Default::default()
+        }
+    }
+
+    /// Steady 5 m/s southerly wind (blowing North), no current and flat sea everywhere
+    fn south_wind_conditions() -> impl Environment {
+        Conditions::new(
+            |_, _| Some(WindData { u: 0.0, v: 5.0 }),
+            |_, _| CurrentData { u: 0.0, v: 0.0 },
+            |_, _| None
+        )
+    }
+
+    /// Departure point of the open water tests, with the default physics and no land
+    fn open_water() -> (Coordinate, PhysicsModel, LandMask) {
+        (Coordinate::new(45.0, -1.0), PhysicsModel::new(), LandMask::new())
+    }
+
     #[test]
     fn test_isochrone_router_default_step() {
         let state = RoutingState::default();
@@ -292,36 +1159,20 @@ mod tests {
 
     #[test]
     fn test_router_expansion() {
-        let start = Coordinate::new(45.0, -1.0);
+        let (start, physics, land_mask) = open_water();
         let dest = Coordinate::new(46.0, -1.0); // North
         let mut router = IsochroneRouter::new(start, dest, 3600.0);
-        
-        let physics = PhysicsModel::new();
-        let land_mask = LandMask::new(); // Empty mask
-        
-        // Setup simple polar: always 5 knots
-        let mut polar = PolarData::default();
-        polar.tws = vec![0.0, 10.0, 20.0];
-        polar.twa = vec![0.0, 90.0, 180.0];
-        polar.speeds = vec![
-            vec![5.0, 5.0, 5.0],
-            vec![5.0, 5.0, 5.0],
-            vec![5.0, 5.0, 5.0],
-        ];
-
-        let initial_state = BoatState {
-            position: start,
-            time: chrono::Utc::now(),
-            elapsed_time: 0.0,
-        };
+
+        let polar = constant_polar(5.0);
+
+        let initial_state = BoatState::new(start, chrono::Utc::now());
 
         let next_front = router.step(
             &[initial_state],
             &physics,
             &polar,
             &land_mask,
-            |_| WindData { u: 0.0, v: 5.0 }, // 5m/s North wind (dir 180 - South)
-            |_| CurrentData { u: 0.0, v: 0.0 }
+            &south_wind_conditions()
         );
 
         assert!(next_front.len() > 1, "Router should expand to multiple points, got {}", next_front.len());
@@ -332,6 +1183,97 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_manoeuvre_penalties() {
+        let penalties = ManoeuvrePenalties::default();
+        let parent = Lineage { parent_index: 0, heading: 45.0, tws: 8.0, twa: 45.0, tack: Tack::Port, sog: 4.0, cog: 45.0, sail: Some(0) };
+
+        assert_eq!(penalties.time_lost(None, Tack::Starboard, 45.0, None), 0.0);
+        assert_eq!(penalties.time_lost(Some(&parent), Tack::Port, 60.0, Some(0)), 0.0);
+        assert_eq!(penalties.time_lost(Some(&parent), Tack::Starboard, 45.0, Some(0)), penalties.tack);
+        assert_eq!(penalties.time_lost(Some(&parent), Tack::Port, 45.0, Some(1)), penalties.sail_change);
+
+        let running = Lineage { twa: 150.0, ..parent };
+        assert_eq!(penalties.time_lost(Some(&running), Tack::Starboard, 160.0, None), penalties.gybe);
+        assert_eq!(ManoeuvrePenalties::none().time_lost(Some(&running), Tack::Starboard, 160.0, Some(2)), 0.0);
+    }
+
+    #[test]
+    fn test_router_vmg_mode() {
+        let (start, physics, land_mask) = open_water();
+        let dest = Coordinate::new(46.0, -1.0);
+        let polar = PolarData::load_from_csv("data/imoca_60.csv").expect("IMOCA 60 polar should load");
+        let wind = WindData::from_speed_direction(7.7, 0.0); // 15 knots from the North
+
+        let mut router = IsochroneRouter::new(start, dest, 3600.0);
+        router.vmg_mode = true;
+        let front = router.step(
+            &[BoatState::new(start, chrono::Utc::now())],
+            &physics, &polar, &land_mask,
+            &Conditions::new(
+                |_, _| Some(wind),
+                |_, _| CurrentData { u: 0.0, v: 0.0 },
+                |_, _| None
+            )
+        );
+        assert!(front.len() > 1);
+
+        let (beat, _) = polar.optimal_beat(15.0);
+        let (run, _) = polar.optimal_run(15.0);
+        for state in &front {
+            let twa = state.lineage.unwrap().twa;
+            assert!(twa >= beat - 1.0 && twa <= run + 1.0, "TWA {} outside [{}, {}]", twa, beat, run);
+        }
+        assert_eq!(IsochroneRouter::vmg_angles(&polar, 15.0), Some((beat, run)));
+
+        // In calm air no angle beats another, so no heading is filtered out
+        let calm_polar = PolarData {
+            tws: vec![0.0, 20.0],
+            twa: vec![0.0, 180.0],
+            speeds: vec![vec![0.0, 10.0], vec![0.0, 10.0]],
+            ..Default::default()
+        };
+        assert_eq!(IsochroneRouter::vmg_angles(&calm_polar, 0.0), None);
+    }
+
+    #[test]
+    fn test_heading_fan() {
+        let fan = HeadingFan { count: 37, half_width: 90.0, adaptive: false };
+        let headings = fan.headings(350.0, &[], 1.0);
+        assert_eq!(headings.len(), 37);
+        assert_eq!((headings[0], headings[36]), (260.0, 80.0));
+        assert!((fan.step() - 5.0).abs() < 1e-6);
+
+        // Adaptive: half the headings evenly spread, the rest around the key headings
+        let fan = HeadingFan { adaptive: true, ..fan };
+        let near = |headings: &[f32], key: f32| headings.iter().filter(|&&h| (h - key).abs() < 5.0).count();
+        let headings = fan.headings(0.0, &[0.0, 45.0, 200.0], 1.0);
+        assert!(near(&headings, 45.0) >= 6, "{:?}", headings);
+        assert!(near(&headings, 70.0) <= 2, "{:?}", headings);
+        assert!(headings.len() <= 37);
+        assert!(headings.iter().all(|h| (0.0..360.0).contains(h)));
+
+        // A sparse front point gets more headings, up to MAX_FAN_DENSITY times
+        assert!(fan.headings(0.0, &[0.0], 2.0).len() > 60);
+        assert!(fan.headings(0.0, &[0.0], 100.0).len() <= (37.0 * MAX_FAN_DENSITY) as usize);
+
+        // The router only tries the configured fan
+        let (start, physics, land_mask) = open_water();
+        let mut router = IsochroneRouter::new(start, Coordinate::new(46.0, -1.0), 3600.0);
+        router.fan = HeadingFan { count: 19, half_width: 45.0, adaptive: false };
+        let polar = constant_polar(5.0);
+        let mut fronts = vec![vec![BoatState::new(start, chrono::Utc::now())]];
+        router.advance(&mut fronts, &physics, &polar, &land_mask, &south_wind_conditions());
+        for state in &fronts[1] {
+            let heading = state.lineage.unwrap().heading;
+            assert!(!(45.5..=314.5).contains(&heading), "Heading {} outside the fan", heading);
+        }
+
+        // Tiny adaptive fans have no room for key clusters but still spread evenly
+        let tiny = HeadingFan { count: 1, half_width: 90.0, adaptive: true };
+        assert_eq!(tiny.headings(0.0, &[10.0], 1.0), vec![90.0, 270.0]);
+    }
+
     #[test]
     fn test_router_land_avoidance() {
         // Create a land barrier between start and destination using REAL land data
@@ -341,25 +1283,21 @@ mod tests {
         let mut router = IsochroneRouter::new(start, dest, 1800.0); // 30 min steps
         
         let physics = PhysicsModel::new();
-        let mut polar = PolarData::default();
-        polar.tws = vec![0.0, 20.0];
-        polar.twa = vec![0.0, 180.0];
-        polar.speeds = vec![vec![10.0, 10.0], vec![10.0, 10.0]];
+        let polar = constant_polar(10.0);
 
         let land_mask = LandMask::load();
-        let initial_state = BoatState {
-            position: start,
-            time: chrono::Utc::now(),
-            elapsed_time: 0.0,
-        };
+        let initial_state = BoatState::new(start, chrono::Utc::now());
 
         let next_front = router.step(
             &[initial_state],
             &physics,
             &polar,
             &land_mask,
-            |_| WindData { u: 0.0, v: 15.0 }, // Strong South wind, moving North
-            |_| CurrentData { u: 0.0, v: 0.0 }
+            &Conditions::new(
+                |_, _| Some(WindData { u: 0.0, v: 15.0 }), // Strong South wind, moving North
+                |_, _| CurrentData { u: 0.0, v: 0.0 },
+                |_, _| None
+            )
         );
 
         for state in &next_front {
@@ -368,29 +1306,462 @@ mod tests {
     }
 
     #[test]
-    fn test_router_zero_speed() {
-        let start = Coordinate::new(45.0, -1.0);
+    fn test_leg_cannot_cross_land() {
+        // A 16 knot hour from south of the Isle of Wight ends in the Solent on some headings,
+        // but every such leg crosses the island
+        let start = Coordinate::new(50.52, -1.30);
+        let mut router = IsochroneRouter::new(start, Coordinate::new(50.78, -1.35), 3600.0);
+
+        let physics = PhysicsModel::new();
+        let polar = constant_polar(16.0);
+
+        let land_mask = LandMask::load();
+        let in_solent = |c: &Coordinate| (50.765..50.80).contains(&c.lat) && (-1.45..-1.25).contains(&c.lon) && !land_mask.is_land(c);
+        assert!(in_solent(&Coordinate::new(50.775, -1.30)), "Off Cowes should be in the Solent");
+
+        for pruning in [PruningStrategy::PolygonUnion, PruningStrategy::BearingSectors { width: 2.0 }] {
+            router.pruning = pruning;
+            router.isochrones.clear();
+            let next_front = router.step(
+                &[BoatState::new(start, chrono::Utc::now())],
+                &physics,
+                &polar,
+                &land_mask,
+                &south_wind_conditions()
+            );
+
+            assert!(!next_front.is_empty());
+            for state in &next_front {
+                assert!(!in_solent(&state.position), "{:?} reached the Solent in one step: {:?}", pruning, state.position);
+                assert_eq!(IsochroneRouter::landfall_fraction(&start, &state.position, &land_mask), None, "Leg to {:?} crosses land", state.position);
+            }
+            assert!(!router.isochrones[0].contains(&Coordinate::new(50.775, -1.30)));
+            // Nor does the reached area bridge the island
+            assert!(land_mask.is_land(&Coordinate::new(50.68, -1.30)));
+            assert!(!router.isochrones[0].contains(&Coordinate::new(50.68, -1.30)), "{:?} area covers the island", pruning);
+        }
+    }
+
+    #[test]
+    fn test_candidate_index_far_point() {
+        // 5 km cells: a point 100 km away, far outside the candidates' cells, still finds one
+        let now = chrono::Utc::now();
+        let candidates = [BoatState::new(Coordinate::new(45.0, -1.0), now), BoatState::new(Coordinate::new(45.01, -1.0), now)];
+        let projection = LocalProjection::new(Coordinate::new(45.0, -1.0));
+        let index = CandidateIndex::new(candidates.iter(), &projection, 5000.0);
+
+        let far = projection.project(&Coordinate::new(45.9, -1.0));
+        let (distance, nearest) = index.nearest(far).unwrap();
+        assert_eq!(nearest.position, candidates[1].position);
+        assert!((distance - 98_900.0).abs() < 500.0, "{} m", distance);
+        assert!(CandidateIndex::new(std::iter::empty(), &projection, 5000.0).nearest(far).is_none());
+    }
+
+    #[test]
+    fn test_front_follows_coast() {
+        // A breakwater north of the start with a coast behind it: the water between the
+        // two can only be reached through the breakwater, so it must stay out of the front
+        let (start, physics, mut land_mask) = open_water();
         let dest = Coordinate::new(46.0, -1.0);
         let mut router = IsochroneRouter::new(start, dest, 3600.0);
-        
+
+        let polar = constant_polar(10.0);
+
+        land_mask.add_land_box(-1.05, -0.95, 45.05, 45.06);
+        land_mask.add_land_box(-1.5, -0.5, 45.1, 46.0);
+
+        let front = router.step(
+            &[BoatState::new(start, chrono::Utc::now())],
+            &physics, &polar, &land_mask, &south_wind_conditions()
+        );
+        assert!(front.len() > 1);
+
+        // Bearing of the breakwater ends seen from the start is about +-35 degrees
+        let lon_scale = start.lat.to_radians().cos();
+        for state in &front {
+            let bearing = ((state.position.lon - start.lon) * lon_scale).atan2(state.position.lat - start.lat).to_degrees();
+            let behind = bearing.abs() < 33.0 && state.position.lat > 45.07;
+            assert!(!behind, "Front point {:?} is behind the breakwater", state.position);
+        }
+    }
+
+    #[test]
+    fn test_no_backtracking() {
+        let (start, physics, land_mask) = open_water();
+        let dest = Coordinate::new(46.0, -1.0);
+        let mut router = IsochroneRouter::new(start, dest, 3600.0);
+
+        let polar = constant_polar(5.0);
+
+        // Area reached at an earlier step
+        let earlier = Polygon::new(LineString::from(vec![(-1.2, 45.0), (-0.8, 45.0), (-0.8, 45.25), (-1.2, 45.25), (-1.2, 45.0)]), vec![]);
+        router.isochrones.push(Isochrone { time: chrono::Utc::now(), elapsed_time: 0.0, area: MultiPolygon(vec![earlier.clone()]) });
+
+        let parent = BoatState::new(Coordinate::new(45.3, -1.0), chrono::Utc::now());
+        let front = router.step(
+            std::slice::from_ref(&parent),
+            &physics, &polar, &land_mask, &south_wind_conditions()
+        );
+        assert!(front.len() > 1);
+        assert_eq!(router.isochrones.len(), 2);
+
+        for state in &front {
+            let position = state.position;
+            let away = IsochroneRouter::calculate_distance(&start, &position) > IsochroneRouter::calculate_distance(&start, &parent.position);
+            let closer = IsochroneRouter::calculate_distance(&position, &dest) < IsochroneRouter::calculate_distance(&parent.position, &dest);
+            assert!(away || closer, "Point {:?} goes back towards the start", position);
+            assert!(!earlier.contains(&Point::new(position.lon, position.lat)), "Point {:?} is in an earlier isochrone", position);
+        }
+        // Abeam of the parent is still fine
+        assert!(front.iter().any(|state| (state.position.lat - 45.3).abs() < 0.01));
+    }
+
+    #[test]
+    fn test_time_to_reach() {
+        let (start, physics, land_mask) = open_water();
+        let dest = Coordinate::new(46.0, -1.0);
+
+        let polar = constant_polar(5.0);
+
+        let start_time = chrono::Utc::now();
+        let mut state = RoutingState {
+            router: IsochroneRouter::new(start, dest, 3600.0),
+            fronts: vec![vec![BoatState::new(start, start_time)]],
+            ..Default::default()
+        };
+        for _ in 0..3 {
+            let state = &mut state;
+            state.router.advance(&mut state.fronts, &physics, &polar, &land_mask, &south_wind_conditions());
+        }
+        assert_eq!(state.isochrones().len(), 3);
+
+        // 1.5 hours at 5 knots to the east
+        let target = IsochroneRouter::calculate_destination(&start, 1.5 * 5.0 * 1852.0, 90.0);
+        let earliest = state.time_to_reach(&target).expect("Target is within the fronts");
+        let hours = (earliest - start_time).num_seconds() as f64 / 3600.0;
+        assert!((hours - 1.5).abs() < 0.1, "Reached after {}h", hours);
+        assert!(!state.is_reachable_by(&target, start_time + chrono::Duration::hours(1)));
+        assert!(state.is_reachable_by(&target, start_time + chrono::Duration::hours(2)));
+
+        assert_eq!(state.time_to_reach(&Coordinate::new(45.5, -1.0)), None);
+
+        // Restarting from the first front drops the later isochrones and is seen as a change
+        let generation = state.isochrone_generation();
+        state.fronts.truncate(1);
+        let state = &mut state;
+        state.router.advance(&mut state.fronts, &physics, &polar, &land_mask, &south_wind_conditions());
+        assert_eq!(state.isochrones().len(), 1);
+        assert!(state.isochrone_generation() > generation);
+    }
+
+    #[test]
+    fn test_route_across_antimeridian() {
+        // Trans-Pacific leg from west of the date line to east of it
+        let start = Coordinate::new(30.0, 178.5);
+        let dest = Coordinate::new(30.0, -179.0);
+        let mut router = IsochroneRouter::new(start, dest, 3.0 * 3600.0);
+        router.fan = HeadingFan::ocean();
+
         let physics = PhysicsModel::new();
         let land_mask = LandMask::new();
-        
-        let polar = PolarData::default(); // Empty = 0 speed
 
-        let initial_state = BoatState {
-            position: start,
-            time: chrono::Utc::now(),
-            elapsed_time: 0.0,
+        let polar = constant_polar(10.0);
+
+        let result = router.solve(
+            BoatState::new(start, chrono::Utc::now()),
+            &physics,
+            &polar,
+            &land_mask,
+            &south_wind_conditions()
+        );
+
+        let SolveOutcome::Arrived { arrival } = result.outcome else {
+            panic!("Expected arrival, got {:?}", result.outcome);
+        };
+        // 130 NM at 10 knots
+        assert!(arrival.elapsed_time > 12.0 * 3600.0 && arrival.elapsed_time < 13.5 * 3600.0, "Arrival after {}s", arrival.elapsed_time);
+        assert!((-180.0..180.0).contains(&arrival.position.lon));
+
+        // Positions are normalised and the fronts stay around the date line instead of
+        // wrapping around the globe
+        for state in result.fronts.iter().flatten() {
+            assert!((-180.0..180.0).contains(&state.position.lon), "Longitude not normalised: {:?}", state.position);
+            assert!(state.position.lon.abs() > 170.0, "Point {:?} is on the wrong side of the globe", state.position);
+        }
+        for leg in &result.route {
+            assert!(IsochroneRouter::calculate_distance(&leg.from, &leg.to) < 60_000.0, "Leg {:?} -> {:?} is too long", leg.from, leg.to);
+        }
+        for isochrone in &result.isochrones {
+            let bounds = isochrone.area.bounding_rect().unwrap();
+            assert!(bounds.width() < 10.0, "Isochrone spans {} degrees of longitude", bounds.width());
+        }
+
+        // Both sides of the date line are found in the areas
+        let last = result.isochrones.last().unwrap();
+        assert!(last.contains(&Coordinate::new(30.0, 179.9)));
+        assert!(last.contains(&Coordinate::new(30.0, -179.9)));
+        assert!(!last.contains(&Coordinate::new(30.0, 0.0)));
+    }
+
+    #[test]
+    fn test_sydney_hobart_route() {
+        // Off Sydney Heads to off Tasman Island, along the New South Wales coast
+        let start = Coordinate::new(-33.85, 151.4);
+        let dest = Coordinate::new(-43.35, 148.2);
+        let mut router = IsochroneRouter::new(start, dest, 6.0 * 3600.0);
+        router.fan = HeadingFan::ocean();
+        router.pruning = PruningStrategy::GreatCircleSectors { width: 5000.0 };
+
+        let physics = PhysicsModel::new();
+        let land_mask = LandMask::load();
+
+        let polar = constant_polar(10.0);
+
+        let result = router.solve(
+            BoatState::new(start, chrono::Utc::now()),
+            &physics,
+            &polar,
+            &land_mask,
+            &south_wind_conditions()
+        );
+
+        let SolveOutcome::Arrived { arrival } = result.outcome else {
+            panic!("Expected arrival, got {:?}", result.outcome);
+        };
+        // About 620 NM at 10 knots
+        assert!(arrival.elapsed_time > 55.0 * 3600.0 && arrival.elapsed_time < 70.0 * 3600.0, "Arrival after {}s", arrival.elapsed_time);
+        for leg in &result.route {
+            assert!(!land_mask.is_land(&leg.to), "Route point on land: {:?}", leg.to);
+            assert!(leg.to.lon > 145.0 && leg.to.lon < 155.0, "Route point {:?} far off the coast", leg.to);
+        }
+    }
+
+    #[test]
+    fn test_front_density_independent_of_latitude() {
+        let (_, physics, land_mask) = open_water();
+
+        let polar = constant_polar(5.0);
+
+        let mut counts = Vec::new();
+        for lat in [0.0, -60.0, -70.0] {
+            let start = Coordinate::new(lat, 10.0);
+            let mut router = IsochroneRouter::new(start, Coordinate::new(lat - 1.0, 10.0), 3600.0);
+            let front = router.step(
+                &[BoatState::new(start, chrono::Utc::now())],
+                &physics,
+                &polar,
+                &land_mask,
+                &south_wind_conditions()
+            );
+
+            // 5 knots for an hour is a 9.26 km circle, resampled every 277.5 m
+            let spacing = 111_000.0 / router.grid_precision;
+            for (i, state) in front.iter().enumerate() {
+                let radius = IsochroneRouter::calculate_distance(&start, &state.position);
+                assert!((radius - 9260.0).abs() < 20.0, "Point {}m from the start at {}°", radius, lat);
+                let gap = IsochroneRouter::calculate_distance(&state.position, &front[(i + 1) % front.len()].position);
+                assert!(gap < 1.05 * spacing, "Front points {}m apart at {}°", gap, lat);
+
+                // Lineage comes from the candidate sailed in the same direction
+                let bearing = IsochroneRouter::calculate_bearing(&start, &state.position);
+                let heading_error = (state.lineage.unwrap().cog - bearing + 540.0).rem_euclid(360.0) - 180.0;
+                assert!(heading_error.abs() <= 1.0, "Lineage heading off by {}° at {}°", heading_error, lat);
+            }
+            let boundary = router.isochrones[0].distance_to_boundary(&start);
+            assert!((boundary - 9260.0).abs() < 50.0, "Isochrone {}m from the start at {}°", boundary, lat);
+            counts.push(front.len() as f64);
+        }
+
+        assert!(counts.iter().all(|&count| (count - counts[0]).abs() <= 0.02 * counts[0]), "Front sizes {:?}", counts);
+    }
+
+    #[test]
+    fn test_distance_to_boundary_at_high_latitude() {
+        // Far from the outline and far north, where degrees of longitude shrink quickly
+        let triangle = Polygon::new(LineString::from(vec![(30.0, 78.0), (30.0, 82.0), (40.0, 80.0), (30.0, 78.0)]), vec![]);
+        let isochrone = Isochrone { time: chrono::Utc::now(), elapsed_time: 0.0, area: MultiPolygon(vec![triangle]) };
+
+        let coord = Coordinate::new(70.0, 0.0);
+        let expected = IsochroneRouter::calculate_distance(&coord, &Coordinate::new(78.0, 30.0));
+        let distance = isochrone.distance_to_boundary(&coord);
+        assert!((distance - expected).abs() < 0.01 * expected, "{} m vs {} m", distance, expected);
+    }
+
+    #[test]
+    fn test_reconstruct_route() {
+        let (start, physics, land_mask) = open_water();
+        let dest = Coordinate::new(46.0, -1.0);
+        let mut router = IsochroneRouter::new(start, dest, 3600.0);
+
+        let polar = constant_polar(5.0);
+
+        let mut fronts = vec![vec![BoatState::new(start, chrono::Utc::now())]];
+        for _ in 0..3 {
+            let next_front = router.step(
+                fronts.last().unwrap(),
+                &physics,
+                &polar,
+                &land_mask,
+                &south_wind_conditions()
+            );
+            fronts.push(next_front);
+        }
+
+        let legs = router.reconstruct_route(&fronts);
+        assert_eq!(legs.len(), 3, "One leg per step expected");
+        assert_eq!(legs[0].from, start);
+        for pair in legs.windows(2) {
+            assert_eq!(pair[0].to, pair[1].from, "Legs must be contiguous");
+            assert!(pair[0].end_time <= pair[1].start_time);
+        }
+
+        // The best arrival point should have headed towards the destination
+        let last = legs.last().unwrap();
+        assert!(last.to.lat > start.lat, "Route should progress north, ended at {:?}", last.to);
+        assert!((last.sog - 5.0 / 1.94384).abs() < 0.01);
+        assert!(last.annotation().starts_with(&format!("{} tack, TWA 1", last.tack)), "Running downwind: {}", last.annotation());
+    }
+
+    #[test]
+    fn test_solve_arrival() {
+        let (start, physics, land_mask) = open_water();
+        let dest = Coordinate::new(45.1, -1.0); // ~11 km North
+        let mut router = IsochroneRouter::new(start, dest, 3600.0);
+
+        let polar = constant_polar(5.0);
+
+        let result = router.solve(
+            BoatState::new(start, chrono::Utc::now()),
+            &physics,
+            &polar,
+            &land_mask,
+            &south_wind_conditions()
+        );
+
+        let SolveOutcome::Arrived { arrival } = result.outcome else {
+            panic!("Expected arrival, got {:?}", result.outcome);
         };
 
+        // (11.1 km - 1 NM) at 5 knots is just over one hour
+        assert!(arrival.elapsed_time > 3600.0 && arrival.elapsed_time < 3900.0, "Arrival after {}s", arrival.elapsed_time);
+        let arrival_dist = IsochroneRouter::calculate_distance(&arrival.position, &dest);
+        assert!((arrival_dist - router.arrival_radius).abs() < 50.0, "Arrival {}m from destination", arrival_dist);
+
+        assert_eq!(result.route.len(), 2);
+        assert_eq!(result.route.last().unwrap().to, arrival.position);
+        assert_eq!(result.route.last().unwrap().end_time, arrival.time);
+    }
+
+    #[test]
+    fn test_sector_pruning() {
+        let (start, physics, land_mask) = open_water();
+        let dest = Coordinate::new(45.5, -1.0);
+
+        let polar = constant_polar(5.0);
+
+        let mut router = IsochroneRouter::new(start, dest, 3600.0);
+        assert!(router.track_distances(&Coordinate::new(45.1, -0.9)).0 > 7000.0, "East of a northbound track is to the right");
+        assert!((router.track_distances(&Coordinate::new(45.1, -1.0)).1 - 11_119.0).abs() < 10.0);
+
+        for pruning in [PruningStrategy::BearingSectors { width: 10.0 }, PruningStrategy::GreatCircleSectors { width: 1000.0 }] {
+            router.pruning = pruning;
+            let mut fronts = vec![vec![BoatState::new(start, chrono::Utc::now())]];
+            for _ in 0..2 {
+                assert!(router.advance(&mut fronts, &physics, &polar, &land_mask, &south_wind_conditions()).is_none());
+            }
+
+            // Every kept point was actually sailed to, and is the furthest of its sector
+            let front = fronts.last().unwrap();
+            assert!(front.iter().all(|state| state.lineage.is_some()));
+            match pruning {
+                PruningStrategy::BearingSectors { .. } => {
+                    assert!(front.len() > 20 && front.len() <= 36, "{} points", front.len());
+                    for state in front {
+                        let dist = IsochroneRouter::calculate_distance(&start, &state.position);
+                        assert!(dist > 18_000.0 && dist < 18_600.0, "Point {}m from the start", dist);
+                    }
+                }
+                _ => {
+                    // 2 hours at 5 knots spans 37 km of cross-track distance
+                    assert!(front.len() > 30 && front.len() <= 38, "{} points", front.len());
+                    let (_, along_track) = router.track_distances(&front[front.len() / 2].position);
+                    assert!(along_track > 18_000.0, "Middle of the front is {}m along track", along_track);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_solve_termination() {
+        let (start, physics, land_mask) = open_water();
+        let dest = Coordinate::new(47.0, -1.0);
+
+        let polar = constant_polar(5.0);
+
+        let mut router = IsochroneRouter::new(start, dest, 3600.0);
+        router.max_duration = 2.0 * 3600.0;
+        let result = router.solve(
+            BoatState::new(start, chrono::Utc::now()),
+            &physics, &polar, &land_mask, &south_wind_conditions()
+        );
+        assert_eq!(result.outcome, SolveOutcome::MaxDurationExceeded);
+        assert_eq!(result.fronts.len(), 3);
+
+        let mut router = IsochroneRouter::new(start, dest, 3600.0);
+        let result = router.solve(
+            BoatState::new(start, chrono::Utc::now()),
+            &physics, &polar, &land_mask,
+            &Conditions::new(
+                |_, _| None,
+                |_, _| CurrentData { u: 0.0, v: 0.0 },
+                |_, _| None
+            )
+        );
+        assert_eq!(result.outcome, SolveOutcome::OutOfWeatherData);
+
+        // Forecast ending two hours after departure
+        let departure = chrono::Utc::now();
+        let mut router = IsochroneRouter::new(start, dest, 3600.0);
+        let result = router.solve(
+            BoatState::new(start, departure),
+            &physics, &polar, &land_mask,
+            &Conditions::new(
+                |_, time| (time < departure + chrono::Duration::hours(2)).then_some(WindData { u: 0.0, v: 5.0 }),
+                |_, _| CurrentData { u: 0.0, v: 0.0 },
+                |_, _| None
+            )
+        );
+        assert_eq!(result.outcome, SolveOutcome::OutOfWeatherData);
+        assert_eq!(result.fronts.len(), 3);
+
+        let mut router = IsochroneRouter::new(start, dest, 3600.0);
+        let result = router.solve(
+            BoatState::new(start, chrono::Utc::now()),
+            &physics, &PolarData::default(), &land_mask, &south_wind_conditions()
+        );
+        assert_eq!(result.outcome, SolveOutcome::FrontCollapsed);
+    }
+
+    #[test]
+    fn test_router_zero_speed() {
+        let (start, physics, land_mask) = open_water();
+        let dest = Coordinate::new(46.0, -1.0);
+        let mut router = IsochroneRouter::new(start, dest, 3600.0);
+
+        let polar = PolarData::default(); // Empty = 0 speed
+
+        let initial_state = BoatState::new(start, chrono::Utc::now());
+
         let next_front = router.step(
             &[initial_state],
             &physics,
             &polar,
             &land_mask,
-            |_| WindData { u: 10.0, v: 10.0 }, 
-            |_| CurrentData { u: 0.0, v: 0.0 }
+            &Conditions::new(
+                |_, _| Some(WindData { u: 10.0, v: 10.0 }), 
+                |_, _| CurrentData { u: 0.0, v: 0.0 },
+                |_, _| None
+            )
         );
 
         // Should return 0 points because no NEW cells were reached (start cell is already marked reached)
@@ -399,29 +1770,25 @@ mod tests {
 
     #[test]
     fn test_router_with_actual_polar() {
-        let start = Coordinate::new(45.0, -1.0);
+        let (start, physics, land_mask) = open_water();
         let dest = Coordinate::new(47.0, -1.0);
         let mut router = IsochroneRouter::new(start, dest, 3600.0);
-        
-        let physics = PhysicsModel::new();
-        let land_mask = LandMask::new();
-        
+
         // Load real polar data
-        let polar = PolarData::load_from_csv("data/imoca_60.csv");
+        let polar = PolarData::load_from_csv("data/imoca_60.csv").expect("IMOCA 60 polar should load");
 
-        let initial_state = BoatState {
-            position: start,
-            time: chrono::Utc::now(),
-            elapsed_time: 0.0,
-        };
+        let initial_state = BoatState::new(start, chrono::Utc::now());
 
         let next_front = router.step(
             &[initial_state],
             &physics,
             &polar,
             &land_mask,
-            |_| WindData { u: 0.0, v: 10.0 }, // 10m/s North wind
-            |_| CurrentData { u: 0.0, v: 0.0 }
+            &Conditions::new(
+                |_, _| Some(WindData { u: 0.0, v: 10.0 }), // 10m/s North wind
+                |_, _| CurrentData { u: 0.0, v: 0.0 },
+                |_, _| None
+            )
         );
 
         assert!(next_front.len() > 10, "Should expand significantly with IMOCA polar, got {}", next_front.len());
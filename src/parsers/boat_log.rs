@@ -0,0 +1,208 @@
+use std::path::Path;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use log::{info, warn};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+
+/// One instant of the boat's log
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LogSample {
+    pub time: DateTime<Utc>,
+    /// True Wind Speed (knots)
+    pub tws: f32,
+    /// Signed True Wind Angle (degrees, positive = starboard tack)
+    pub twa: f32,
+    /// Speed through water (knots)
+    pub stw: f32,
+}
+
+/// Reads the boat's instrument log, either NMEA 0183 (MWV, VHW, RMC) or CSV with a
+/// `time,tws,twa,stw` header (RFC 3339 times, knots and degrees).
+pub struct BoatLogLoader;
+
+/// Latest instrument values while replaying NMEA sentences
+#[derive(Default)]
+struct NmeaState {
+    date: Option<NaiveDate>,
+    time: Option<NaiveTime>,
+    stw: Option<f32>,
+}
+
+impl BoatLogLoader {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Loads a log file, NMEA if its first non-empty line starts with `$` or `!`, CSV otherwise
+    pub fn load<P: AsRef<Path>>(&self, path: P) -> Result<Vec<LogSample>, Box<dyn std::error::Error>> {
+        info!("Loading boat log from: {:?}", path.as_ref());
+        let mut reader = BufReader::new(File::open(path)?);
+
+        let is_nmea = loop {
+            let buffer = reader.fill_buf()?;
+            match buffer.iter().find(|b| !b.is_ascii_whitespace()) {
+                Some(&b) => break b == b'$' || b == b'!',
+                None if buffer.is_empty() => break false,
+                None => {
+                    let len = buffer.len();
+                    reader.consume(len);
+                }
+            }
+        };
+
+        let samples = if is_nmea { self.parse_nmea(reader)? } else { self.parse_csv(reader)? };
+        info!("Loaded {} log samples", samples.len());
+        Ok(samples)
+    }
+
+    /// Checks the `*hh` XOR checksum when present and returns the sentence fields
+    fn nmea_fields(line: &str) -> Option<Vec<&str>> {
+        let body = line.trim().strip_prefix('$')?;
+        let (data, checksum) = match body.split_once('*') {
+            Some((data, checksum)) => (data, Some(checksum)),
+            None => (body, None),
+        };
+        if let Some(checksum) = checksum {
+            let expected = u8::from_str_radix(checksum.trim(), 16).ok()?;
+            if data.bytes().fold(0u8, |acc, b| acc ^ b) != expected {
+                return None;
+            }
+        }
+        Some(data.split(',').collect())
+    }
+
+    /// True wind from apparent wind angle/speed and speed through water
+    fn true_wind(awa: f32, aws: f32, stw: f32) -> (f32, f32) {
+        let (sin, cos) = awa.to_radians().sin_cos();
+        let x = aws * cos - stw;
+        let y = aws * sin;
+        ((x * x + y * y).sqrt(), y.atan2(x).to_degrees())
+    }
+
+    /// Replays MWV/VHW/RMC sentences: every wind sentence with a known time and
+    /// speed through water gives one sample. Apparent wind is converted to true wind.
+    pub fn parse_nmea<R: BufRead>(&self, reader: R) -> Result<Vec<LogSample>, Box<dyn std::error::Error>> {
+        let mut state = NmeaState::default();
+        let mut samples = Vec::new();
+        let mut rejected = 0;
+
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let Some(fields) = Self::nmea_fields(&line) else {
+                rejected += 1;
+                continue;
+            };
+            let number = |i: usize| fields.get(i).and_then(|v| v.parse::<f32>().ok());
+            let sentence = fields[0].get(2..).unwrap_or_default();
+
+            match sentence {
+                "RMC" => {
+                    state.time = fields.get(1).and_then(|t| NaiveTime::parse_from_str(t, "%H%M%S%.f").ok());
+                    state.date = fields.get(9).and_then(|d| NaiveDate::parse_from_str(d, "%d%m%y").ok());
+                }
+                "VHW" => {
+                    if let Some(stw) = number(5) {
+                        state.stw = Some(stw);
+                    }
+                }
+                "MWV" => {
+                    let valid = fields.get(5).is_some_and(|status| status.starts_with('A'));
+                    let (Some(angle), Some(speed), Some(unit), Some(reference)) = (number(1), number(3), fields.get(4), fields.get(2)) else { continue };
+                    let (Some(date), Some(time), Some(stw)) = (state.date, state.time, state.stw) else { continue };
+                    if !valid {
+                        continue;
+                    }
+
+                    let speed = match *unit {
+                        "M" => speed * 1.94384,
+                        "K" => speed / 1.852,
+                        _ => speed,
+                    };
+                    let (tws, twa) = if *reference == "T" {
+                        (speed, if angle > 180.0 { angle - 360.0 } else { angle })
+                    } else {
+                        Self::true_wind(angle, speed, stw)
+                    };
+
+                    samples.push(LogSample { time: date.and_time(time).and_utc(), tws, twa, stw });
+                }
+                _ => {}
+            }
+        }
+
+        if rejected > 0 {
+            warn!("Skipped {} NMEA sentences with a bad checksum or framing", rejected);
+        }
+        Ok(samples)
+    }
+
+    pub fn parse_csv<R: BufRead>(&self, reader: R) -> Result<Vec<LogSample>, Box<dyn std::error::Error>> {
+        let mut lines = reader.lines();
+        let header = lines.next().ok_or("Empty boat log")??;
+        let columns: Vec<String> = header.split(',').map(|c| c.trim().to_lowercase()).collect();
+        let column = |name: &str| columns.iter().position(|c| c == name).ok_or(format!("Missing {} column", name));
+        let (time_col, tws_col, twa_col, stw_col) = (column("time")?, column("tws")?, column("twa")?, column("stw")?);
+
+        let mut samples = Vec::new();
+        for (line_no, line) in lines.enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let parts: Vec<&str> = line.split(',').map(str::trim).collect();
+            let number = |col: usize| parts.get(col).and_then(|v| v.parse::<f32>().ok());
+            let time = parts.get(time_col).and_then(|v| DateTime::parse_from_rfc3339(v).ok());
+
+            let (Some(time), Some(tws), Some(twa), Some(stw)) = (time, number(tws_col), number(twa_col), number(stw_col)) else {
+                warn!("Skipping malformed log line {}", line_no + 2);
+                continue;
+            };
+            samples.push(LogSample { time: time.with_timezone(&Utc), tws, twa, stw });
+        }
+        Ok(samples)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentence(data: &str) -> String {
+        let checksum = data.bytes().fold(0u8, |acc, b| acc ^ b);
+        format!("${}*{:02X}", data, checksum)
+    }
+
+    #[test]
+    fn test_parse_nmea() {
+        let log = [
+            sentence("IIMWV,090,T,12.0,N,A"), // No time yet
+            sentence("GPRMC,120000.00,A,4700.00,N,00300.00,W,8.1,250.0,210625,,,A"),
+            sentence("IIVHW,250.0,T,,M,8.0,N,14.8,K"),
+            sentence("IIMWV,270.0,T,12.0,N,A"),
+            "$IIMWV,045,T,12.0,N,A*00".to_string(), // Bad checksum
+            sentence("IIMWV,090,T,12.0,N,V"), // Invalid data
+            sentence("IIMWV,090.0,R,10.0,N,A"),
+        ].join("\n");
+
+        let samples = BoatLogLoader::new().parse_nmea(log.as_bytes()).unwrap();
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[0].time.to_rfc3339(), "2025-06-21T12:00:00+00:00");
+        assert_eq!((samples[0].tws, samples[0].twa, samples[0].stw), (12.0, -90.0, 8.0));
+
+        // 10 kt apparent on the beam at 8 kt: true wind is further aft and stronger
+        let (tws, twa) = (samples[1].tws, samples[1].twa);
+        assert!((tws - 12.806).abs() < 1e-2, "TWS {}", tws);
+        assert!((twa - 128.66).abs() < 1e-1, "TWA {}", twa);
+    }
+
+    #[test]
+    fn test_parse_csv_log() {
+        let csv = "time,tws,twa,stw\n2025-06-21T12:00:00Z,12,-90,8\nbad,,,\n";
+        let samples = BoatLogLoader::new().parse_csv(csv.as_bytes()).unwrap();
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].twa, -90.0);
+    }
+}
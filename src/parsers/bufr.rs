@@ -1,17 +1,202 @@
-// Placeholder for BUFR parser
-
 use std::path::Path;
 use log::info;
+use chrono::NaiveDate;
+use eccodes::{CodesFile, ProductKind, KeyRead, KeyWrite, FallibleIterator};
+
+use crate::engine::models::{Coordinate, ObservationSource, WindData, WindObservation};
+
+/// eccodes decodes missing BUFR values as -1e100: anything at or below this threshold is missing
+const MISSING_THRESHOLD: f64 = -1e99;
+
+/// Wind speed keys: surface stations, then scatterometer products
+const WIND_SPEED_KEYS: &[&str] = &["windSpeed", "windSpeedAt10M"];
+const WIND_DIRECTION_KEYS: &[&str] = &["windDirection", "windDirectionAt10M"];
 
 pub struct BufrLoader;
 
+/// Per-subset values decoded from one BUFR message. Arrays of length 1 apply to all subsets.
+#[derive(Debug, Default)]
+struct DecodedSubsets {
+    lats: Vec<f64>,
+    lons: Vec<f64>,
+    years: Vec<f64>,
+    months: Vec<f64>,
+    days: Vec<f64>,
+    hours: Vec<f64>,
+    minutes: Vec<f64>,
+    speeds: Vec<f64>,
+    directions: Vec<f64>,
+}
+
+impl DecodedSubsets {
+    fn into_observations(self, source: ObservationSource) -> Vec<WindObservation> {
+        let arrays = [
+            &self.lats, &self.lons, &self.years, &self.months, &self.days,
+            &self.hours, &self.minutes, &self.speeds, &self.directions,
+        ];
+        if arrays.iter().any(|a| a.is_empty()) {
+            return Vec::new();
+        }
+        let count = arrays.iter().map(|a| a.len()).max().unwrap_or(0);
+
+        // Broadcast single values, reject missing ones
+        let value = |values: &[f64], i: usize| {
+            let v = if values.len() == 1 { values[0] } else { *values.get(i)? };
+            (v > MISSING_THRESHOLD).then_some(v)
+        };
+
+        let mut observations = Vec::with_capacity(count);
+        for i in 0..count {
+            let fields = (
+                value(&self.lats, i), value(&self.lons, i),
+                value(&self.years, i), value(&self.months, i), value(&self.days, i),
+                value(&self.hours, i), value(&self.minutes, i),
+                value(&self.speeds, i), value(&self.directions, i),
+            );
+            let (Some(lat), Some(lon), Some(year), Some(month), Some(day), Some(hour), Some(minute), Some(speed), Some(direction)) = fields else {
+                continue;
+            };
+
+            let Some(time) = NaiveDate::from_ymd_opt(year as i32, month as u32, day as u32)
+                .and_then(|d| d.and_hms_opt(hour as u32, minute as u32, 0))
+                .map(|t| t.and_utc())
+            else {
+                continue;
+            };
+
+            observations.push(WindObservation {
+                source,
+                position: Coordinate::new(lat, lon),
+                time,
+                wind: WindData::from_speed_direction(speed as f32, direction as f32),
+            });
+        }
+
+        observations
+    }
+}
+
 impl BufrLoader {
     pub fn new() -> Self {
         Self
     }
-    
-    pub fn load<P: AsRef<Path>>(&self, path: P) {
-        info!("Loading BUFR data from: {:?}", path.as_ref());
-        // BUFR parsing logic using eccodes will go here
+
+    /// Classifies a message from its BUFR data category and international sub-category
+    /// (WMO Common Code Table C-13)
+    pub fn classify(data_category: i64, sub_category: i64) -> ObservationSource {
+        match (data_category, sub_category) {
+            (1, 25) => ObservationSource::Buoy,
+            (1, _) => ObservationSource::Ship,
+            (12, _) => ObservationSource::Scatterometer,
+            _ => ObservationSource::Other,
+        }
+    }
+
+    /// Loads buoy, ship and scatterometer wind observations from a BUFR file using eccodes
+    pub fn load<P: AsRef<Path>>(&self, path: P) -> Result<Vec<WindObservation>, Box<dyn std::error::Error>> {
+        let path = path.as_ref();
+        info!("Loading BUFR data from: {:?}", path);
+
+        let path_str = path.to_str().unwrap();
+        let mut file = CodesFile::new_from_file(path_str, ProductKind::BUFR)?;
+        let mut observations = Vec::new();
+
+        let mut iter = file.ref_message_iter();
+
+        loop {
+            match iter.next() {
+                Ok(Some(message)) => {
+                    // Data section keys are only available once the message is unpacked
+                    let mut message = message.try_clone()?;
+                    message.write_key_unchecked("unpack", 1)?;
+
+                    let data_category: i64 = message.read_key("dataCategory").unwrap_or(-1);
+                    let sub_category: i64 = message.read_key("internationalDataSubCategory").unwrap_or(-1);
+                    let source = Self::classify(data_category, sub_category);
+
+                    let read = |names: &[&str]| -> Vec<f64> {
+                        names.iter()
+                            .find_map(|name| KeyRead::<Vec<f64>>::read_key(&message, name).ok())
+                            .unwrap_or_default()
+                    };
+
+                    let decoded = DecodedSubsets {
+                        lats: read(&["latitude"]),
+                        lons: read(&["longitude"]),
+                        years: read(&["year"]),
+                        months: read(&["month"]),
+                        days: read(&["day"]),
+                        hours: read(&["hour"]),
+                        minutes: read(&["minute"]),
+                        speeds: read(WIND_SPEED_KEYS),
+                        directions: read(WIND_DIRECTION_KEYS),
+                    };
+
+                    observations.extend(decoded.into_observations(source));
+                },
+                Ok(None) => break,
+                Err(eccodes::CodesError::Internal(eccodes::errors::CodesInternal::CodesPrematureEndOfFile)) => {
+                    log::warn!("BUFR file reached premature EOF (likely truncated). Proceeding with data extracted so far.");
+                    break;
+                },
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        info!("Successfully loaded {} wind observations.", observations.len());
+        Ok(observations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(BufrLoader::classify(1, 25), ObservationSource::Buoy);
+        assert_eq!(BufrLoader::classify(1, 0), ObservationSource::Ship);
+        assert_eq!(BufrLoader::classify(12, 10), ObservationSource::Scatterometer);
+        assert_eq!(BufrLoader::classify(0, 0), ObservationSource::Other);
+    }
+
+    #[test]
+    fn test_decoded_subsets() {
+        // Compressed scatterometer message: one date for three wind cells, one missing
+        let decoded = DecodedSubsets {
+            lats: vec![45.0, 45.1, 45.2],
+            lons: vec![-10.0, -10.0, -10.0],
+            years: vec![2024.0],
+            months: vec![1.0],
+            days: vec![15.0],
+            hours: vec![9.0],
+            minutes: vec![30.0],
+            speeds: vec![10.0, -1e100, 12.0],
+            directions: vec![270.0, 270.0, 180.0],
+        };
+
+        let observations = decoded.into_observations(ObservationSource::Scatterometer);
+        assert_eq!(observations.len(), 2);
+        assert_eq!(observations[0].time.format("%Y-%m-%d %H:%M").to_string(), "2024-01-15 09:30");
+        assert!((observations[0].wind.u - 10.0).abs() < 1e-4, "West wind blows East");
+        assert_eq!(observations[1].position, Coordinate::new(45.2, -10.0));
+        assert!((observations[1].wind.v - 12.0).abs() < 1e-4, "South wind blows North");
+    }
+
+    #[test]
+    fn test_load_sample() {
+        // Two buoys and two ships, the last one without a wind speed
+        let observations = BufrLoader::new().load("data/observations_sample.bufr").unwrap();
+        assert_eq!(observations.len(), 3);
+
+        let buoy = &observations[0];
+        assert_eq!(buoy.source, ObservationSource::Buoy);
+        assert!((buoy.position.lat - 47.55).abs() < 1e-6 && (buoy.position.lon + 8.40).abs() < 1e-6);
+        assert_eq!(buoy.time.format("%Y-%m-%d %H:%M").to_string(), "2024-01-15 09:00");
+        assert!((buoy.wind.speed() - 12.3).abs() < 1e-3);
+        assert!((buoy.wind.direction() - 250.0).abs() < 1e-2);
+
+        assert_eq!(observations[2].source, ObservationSource::Ship);
+        assert_eq!(observations[2].time.format("%Y-%m-%d %H:%M").to_string(), "2024-01-15 09:30");
     }
 }
@@ -0,0 +1,126 @@
+use std::path::Path;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use log::{info, warn};
+use chrono::{DateTime, Duration, Utc};
+
+use crate::engine::models::{Coordinate, ObservationSource, WindData, WindObservation};
+
+const KNOTS_TO_MS: f64 = 0.514444;
+
+/// Loads buoy reports from a simple CSV file with a header line, e.g.
+/// `time,lat,lon,speed_kts,direction` with RFC 3339 times, or times relative to
+/// `reference_time` written as `+6h`.
+/// Wind speed may be given as `speed_kts` or `speed_ms`, direction is "coming from" in degrees.
+pub struct BuoyCsvLoader {
+    /// Time that relative report times count from, usually the start of the loaded forecast
+    pub reference_time: Option<DateTime<Utc>>,
+}
+
+impl BuoyCsvLoader {
+    pub fn new() -> Self {
+        Self { reference_time: None }
+    }
+
+    pub fn with_reference_time(reference_time: DateTime<Utc>) -> Self {
+        Self { reference_time: Some(reference_time) }
+    }
+
+    /// RFC 3339 time, or `+<hours>h` after `reference_time`
+    fn parse_time(&self, value: &str) -> Option<DateTime<Utc>> {
+        match value.strip_prefix('+').and_then(|offset| offset.strip_suffix('h')) {
+            Some(hours) => {
+                let hours = hours.parse::<f64>().ok()?;
+                self.reference_time.map(|reference| reference + Duration::seconds((hours * 3600.0) as i64))
+            }
+            None => DateTime::parse_from_rfc3339(value).ok().map(|time| time.with_timezone(&Utc)),
+        }
+    }
+
+    pub fn load<P: AsRef<Path>>(&self, path: P) -> Result<Vec<WindObservation>, Box<dyn std::error::Error>> {
+        info!("Loading buoy reports from CSV: {:?}", path.as_ref());
+        let file = File::open(path)?;
+        self.parse(BufReader::new(file))
+    }
+
+    pub fn parse<R: BufRead>(&self, reader: R) -> Result<Vec<WindObservation>, Box<dyn std::error::Error>> {
+        let mut lines = reader.lines();
+        let header = lines.next().ok_or("Empty buoy CSV")??;
+        let columns: Vec<String> = header.split(',').map(|c| c.trim().to_lowercase()).collect();
+        let column = |names: &[&str]| columns.iter().position(|c| names.contains(&c.as_str()));
+
+        let time_col = column(&["time"]).ok_or("Missing time column")?;
+        let lat_col = column(&["lat", "latitude"]).ok_or("Missing lat column")?;
+        let lon_col = column(&["lon", "longitude"]).ok_or("Missing lon column")?;
+        let dir_col = column(&["direction", "dir"]).ok_or("Missing direction column")?;
+        let (speed_col, speed_scale) = match (column(&["speed_kts"]), column(&["speed_ms"])) {
+            (Some(col), _) => (col, KNOTS_TO_MS),
+            (None, Some(col)) => (col, 1.0),
+            (None, None) => return Err("Missing speed_kts or speed_ms column".into()),
+        };
+
+        let mut observations = Vec::new();
+        for (line_no, line) in lines.enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let parts: Vec<&str> = line.split(',').map(str::trim).collect();
+            let number = |col: usize| parts.get(col).and_then(|v| v.parse::<f64>().ok());
+
+            let time = parts.get(time_col).and_then(|v| self.parse_time(v));
+            let (Some(time), Some(lat), Some(lon), Some(speed), Some(direction)) =
+                (time, number(lat_col), number(lon_col), number(speed_col), number(dir_col))
+            else {
+                warn!("Skipping malformed buoy report on line {}", line_no + 2);
+                continue;
+            };
+
+            observations.push(WindObservation {
+                source: ObservationSource::Buoy,
+                position: Coordinate::new(lat, lon),
+                time,
+                wind: WindData::from_speed_direction((speed * speed_scale) as f32, direction as f32),
+            });
+        }
+
+        info!("Loaded {} buoy reports", observations.len());
+        Ok(observations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_parse_buoy_csv() {
+        let csv = "time,lat,lon,speed_kts,direction\n\
+                   2025-01-01T12:00:00Z,47.5,-8.5,20,270\n\
+                   2025-01-01T13:00:00Z,47.5,-8.5,bad,270\n\
+                   \n\
+                   2025-01-01T14:00:00Z,48.0,-9.0,10,180\n";
+        let observations = BuoyCsvLoader::new().parse(csv.as_bytes()).unwrap();
+
+        assert_eq!(observations.len(), 2);
+        assert_eq!(observations[0].source, ObservationSource::Buoy);
+        assert!((observations[0].wind.speed() - 10.29).abs() < 0.01);
+        assert!((observations[0].wind.direction() - 270.0).abs() < 0.01);
+        assert_eq!(observations[1].position.lat, 48.0);
+
+        assert!(BuoyCsvLoader::new().parse("time,lat,lon,direction\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_relative_report_times() {
+        let csv = "time,lat,lon,speed_kts,direction\n+0h,47.5,-8.5,20,270\n+1.5h,47.5,-8.5,20,270\n";
+        // Relative times need a reference
+        assert!(BuoyCsvLoader::new().parse(csv.as_bytes()).unwrap().is_empty());
+
+        let reference = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let observations = BuoyCsvLoader::with_reference_time(reference).parse(csv.as_bytes()).unwrap();
+        assert_eq!(observations[0].time, reference);
+        assert_eq!(observations[1].time.to_rfc3339(), "2025-01-01T13:30:00+00:00");
+    }
+}
@@ -1,50 +1,180 @@
+use std::collections::BTreeMap;
 use std::path::Path;
 use log::info;
+use chrono::{DateTime, NaiveDate, Utc};
 use eccodes::{CodesFile, ProductKind, KeyRead, DynamicKeyType, FallibleIterator};
 
-use crate::engine::models::{Coordinate, WindData, CurrentData};
+use crate::engine::models::{CurrentField, GridGeometry, GridSnapshot, WaveField, WaveSnapshot, WindField};
 
 pub struct GribLoader {
     // This will eventually hold a structured representation of the grid
 }
 
+/// 10 m wind components
+const WIND_U_NAMES: &[&str] = &["10u"];
+const WIND_V_NAMES: &[&str] = &["10v"];
+
+/// Surface current components: GRIB2 WMO names, then Copernicus Marine names
+const CURRENT_U_NAMES: &[&str] = &["ucur", "uo"];
+const CURRENT_V_NAMES: &[&str] = &["vcur", "vo"];
+
+/// Significant wave height, mean wave direction and mean wave period
+const WAVE_NAMES: &[&str] = &["swh", "mwd", "mwp"];
+
+/// Values at or above this magnitude are treated as missing
+const MISSING_VALUE: f64 = 9999.0;
+
+/// One decoded GRIB message: a parameter at a valid time and level
+struct GribField {
+    short_name: String,
+    valid_time: DateTime<Utc>,
+    level: i64,
+    values: Vec<f64>,
+    grid: Option<GridGeometry>,
+}
+
 impl GribLoader {
     pub fn new() -> Self {
         Self {}
     }
 
-    /// Loads a GRIB file and extracts U and V components for wind using eccodes
-    pub fn load_wind_data<P: AsRef<Path>>(&self, path: P) -> Result<Vec<(Coordinate, WindData)>, Box<dyn std::error::Error>> {
-        let path = path.as_ref();
-        info!("Loading wind data from GRIB file: {:?}", path);
-        
+    /// Computes the valid time of a GRIB message from its reference date/time and step.
+    /// `data_date` is `YYYYMMDD`, `data_time` is `HHMM` and `step_range` is either a single
+    /// step ("6") or a range ("0-6") whose end is used. Steps are in `step_units` (the
+    /// `stepUnits` key: "m", "h", "3h", "6h", "12h", "D" or "s") unless they carry their
+    /// own unit suffix ("15m"), as recent ecCodes versions write for non-hourly steps.
+    pub fn valid_time(data_date: i64, data_time: i64, step_range: &str, step_units: &str) -> Option<DateTime<Utc>> {
+        let date = NaiveDate::from_ymd_opt(
+            (data_date / 10000) as i32,
+            ((data_date / 100) % 100) as u32,
+            (data_date % 100) as u32,
+        )?;
+        let reference = date.and_hms_opt((data_time / 100) as u32, (data_time % 100) as u32, 0)?;
+
+        let step = step_range.rsplit('-').next()?.trim();
+        let digits = step.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+        let units = if digits.len() < step.len() { &step[digits.len()..] } else { step_units };
+        let step: i64 = digits.parse().ok()?;
+
+        Some(reference.and_utc() + Self::step_duration(step, units)?)
+    }
+
+    /// Duration of `step` forecast steps in the given `stepUnits`.
+    /// Months and years have no fixed length and are not supported.
+    fn step_duration(step: i64, units: &str) -> Option<chrono::Duration> {
+        match units {
+            "s" => Some(chrono::Duration::seconds(step)),
+            "m" => Some(chrono::Duration::minutes(step)),
+            "h" => Some(chrono::Duration::hours(step)),
+            "3h" => Some(chrono::Duration::hours(3 * step)),
+            "6h" => Some(chrono::Duration::hours(6 * step)),
+            "12h" => Some(chrono::Duration::hours(12 * step)),
+            "D" => Some(chrono::Duration::days(step)),
+            _ => None,
+        }
+    }
+
+    /// Abbreviation of a `stepUnits` code table value, for ecCodes builds that return
+    /// the key as an integer
+    fn step_units_name(code: i64) -> &'static str {
+        match code {
+            0 => "m",
+            2 => "D",
+            10 => "3h",
+            11 => "6h",
+            12 => "12h",
+            13 => "s",
+            _ => "h",
+        }
+    }
+
+    /// Reads the regular lat/lon grid geometry of a GRIB message.
+    /// Returns `None` for other grid types (Lambert, reduced Gaussian...).
+    fn read_grid_geometry<M>(message: &M) -> Option<GridGeometry>
+    where
+        M: KeyRead<String> + KeyRead<f64> + KeyRead<i64>,
+    {
+        let grid_type: String = message.read_key("gridType").ok()?;
+        if grid_type != "regular_ll" {
+            log::warn!("Unsupported GRIB grid type {}", grid_type);
+            return None;
+        }
+
+        let lat0: f64 = message.read_key("latitudeOfFirstGridPointInDegrees").ok()?;
+        let mut lon0: f64 = message.read_key("longitudeOfFirstGridPointInDegrees").ok()?;
+        let i_increment: f64 = message.read_key("iDirectionIncrementInDegrees").ok()?;
+        let j_increment: f64 = message.read_key("jDirectionIncrementInDegrees").ok()?;
+        let ni: i64 = message.read_key("Ni").ok()?;
+        let nj: i64 = message.read_key("Nj").ok()?;
+        let i_scans_negatively: i64 = message.read_key("iScansNegatively").unwrap_or(0);
+        let j_scans_positively: i64 = message.read_key("jScansPositively").unwrap_or(0);
+
+        // Normalize longitude from [0, 360] to [-180, 180]
+        if lon0 >= 180.0 {
+            lon0 -= 360.0;
+        }
+
+        Some(GridGeometry {
+            lat0,
+            lon0,
+            dlat: if j_scans_positively == 1 { j_increment } else { -j_increment },
+            dlon: if i_scans_negatively == 1 { -i_increment } else { i_increment },
+            ni: ni as usize,
+            nj: nj as usize,
+        })
+    }
+
+    /// Reads every message whose `shortName` is in `short_names`
+    fn read_fields(&self, path: &Path, short_names: &[&str]) -> Result<Vec<GribField>, Box<dyn std::error::Error>> {
         let path_str = path.to_str().unwrap();
         
         let mut file = CodesFile::new_from_file(path_str, ProductKind::GRIB)?;
-        
-        // We need to store both U and V before constructing the final WindData
-        // (Assuming U and V come in separate messages, which is standard)
-        let mut u_components: Vec<f64> = Vec::new();
-        let mut v_components: Vec<f64> = Vec::new();
-        let mut lats: Vec<f64> = Vec::new();
-        let mut lons: Vec<f64> = Vec::new();
+        let mut fields = Vec::new();
 
         let mut iter = file.ref_message_iter();
         
         loop {
             match iter.next() {
                 Ok(Some(message)) => {
-                    if let Ok(DynamicKeyType::Str(name)) = message.read_key_dynamic("shortName") {
-                        if name == "10u" {
-                            info!("Extracting 10u (U-wind component)");
-                            u_components = message.read_key("values").unwrap_or_default();
-                            lats = message.read_key("latitudes").unwrap_or_default();
-                            lons = message.read_key("longitudes").unwrap_or_default();
-                        } else if name == "10v" {
-                            info!("Extracting 10v (V-wind component)");
-                            v_components = message.read_key("values").unwrap_or_default();
-                        }
-                    }
+                    let Ok(DynamicKeyType::Str(name)) = message.read_key_dynamic("shortName") else { continue };
+                    if !short_names.contains(&name.as_str()) { continue; }
+
+                    let data_date = match message.read_key_dynamic("dataDate") {
+                        Ok(DynamicKeyType::Int(v)) => v,
+                        _ => 0,
+                    };
+                    let data_time = match message.read_key_dynamic("dataTime") {
+                        Ok(DynamicKeyType::Int(v)) => v,
+                        _ => 0,
+                    };
+                    let step_range = match message.read_key_dynamic("stepRange") {
+                        Ok(DynamicKeyType::Str(v)) => v,
+                        Ok(DynamicKeyType::Int(v)) => v.to_string(),
+                        _ => "0".to_string(),
+                    };
+                    let step_units = match message.read_key_dynamic("stepUnits") {
+                        Ok(DynamicKeyType::Str(v)) => v,
+                        Ok(DynamicKeyType::Int(v)) => Self::step_units_name(v).to_string(),
+                        _ => "h".to_string(),
+                    };
+                    let level = match message.read_key_dynamic("level") {
+                        Ok(DynamicKeyType::Int(v)) => v,
+                        _ => 0,
+                    };
+
+                    let Some(valid_time) = Self::valid_time(data_date, data_time, &step_range, &step_units) else {
+                        log::warn!("Skipping {} message with invalid date {} {} step {} {}", name, data_date, data_time, step_range, step_units);
+                        continue;
+                    };
+
+                    info!("Extracting {} at level {} valid at {}", name, level, valid_time);
+                    fields.push(GribField {
+                        values: message.read_key("values").unwrap_or_default(),
+                        grid: Self::read_grid_geometry(&message),
+                        short_name: name,
+                        valid_time,
+                        level,
+                    });
                 },
                 Ok(None) => break,
                 Err(eccodes::CodesError::Internal(eccodes::errors::CodesInternal::CodesPrematureEndOfFile)) => {
@@ -54,37 +184,208 @@ impl GribLoader {
                 Err(e) => return Err(e.into()),
             }
         }
-        
-        // Merge them into Coordinate -> WindData
-        let mut wind_data = Vec::new();
-        
-        let point_count = u_components.len().min(v_components.len()).min(lats.len()).min(lons.len());
-        
-        for i in 0..point_count {
-            let mut lon = lons[i];
-            // Normalize longitude from [0, 360] to [-180, 180]
-            if lon > 180.0 {
-                lon -= 360.0;
+
+        Ok(fields)
+    }
+
+    /// Pairs U and V fields by valid time into gridded snapshots. When several
+    /// levels are present (e.g. ocean depths) the shallowest one is kept.
+    fn assemble_vector_steps(fields: Vec<GribField>, u_names: &[&str], v_names: &[&str]) -> Vec<GridSnapshot> {
+        // U and V come in separate messages, which is standard. We store both per
+        // valid time before constructing the final snapshot.
+        let mut steps: BTreeMap<DateTime<Utc>, (Option<GribField>, Option<GribField>)> = BTreeMap::new();
+
+        for field in fields {
+            let step = steps.entry(field.valid_time).or_default();
+            let slot = if u_names.contains(&field.short_name.as_str()) {
+                &mut step.0
+            } else if v_names.contains(&field.short_name.as_str()) {
+                &mut step.1
+            } else {
+                continue;
+            };
+
+            if slot.as_ref().is_none_or(|existing| field.level < existing.level) {
+                *slot = Some(field);
             }
-            
-            wind_data.push((
-                Coordinate::new(lats[i], lon),
-                WindData { u: u_components[i] as f32, v: v_components[i] as f32 }
-            ));
+        }
+
+        let mut snapshots = Vec::new();
+        for (valid_time, step) in steps {
+            let (Some(u), Some(v)) = step else {
+                log::warn!("Missing U or V component at {}, skipping", valid_time);
+                continue;
+            };
+            let Some(grid) = u.grid else {
+                log::warn!("No regular grid for step at {}, skipping", valid_time);
+                continue;
+            };
+            if u.values.len() != grid.len() || v.values.len() != grid.len() {
+                log::warn!("Incomplete step at {} ({} u / {} v values for {} grid points), skipping",
+                    valid_time, u.values.len(), v.values.len(), grid.len());
+                continue;
+            }
+
+            // Missing values (land points in ocean models) are encoded as 9999 and become NaN
+            let to_f32 = |x: &f64| if x.abs() >= MISSING_VALUE { f32::NAN } else { *x as f32 };
+            let u_values = u.values.iter().map(to_f32).collect();
+            let v_values = v.values.iter().map(to_f32).collect();
+            snapshots.push(GridSnapshot::new(valid_time, grid, u_values, v_values));
+        }
+
+        snapshots
+    }
+
+    /// Groups wave fields by valid time into gridded snapshots. Significant wave height
+    /// is required, direction and period are optional. Missing values become NaN.
+    fn assemble_wave_steps(fields: Vec<GribField>) -> Vec<WaveSnapshot> {
+        let mut steps: BTreeMap<DateTime<Utc>, Vec<GribField>> = BTreeMap::new();
+        for field in fields {
+            steps.entry(field.valid_time).or_default().push(field);
+        }
+
+        let mut snapshots = Vec::new();
+        for (valid_time, step_fields) in steps {
+            let Some(swh) = step_fields.iter().find(|f| f.short_name == "swh") else {
+                log::warn!("Missing significant wave height at {}, skipping", valid_time);
+                continue;
+            };
+            let Some(grid) = swh.grid else {
+                log::warn!("No regular grid for wave step at {}, skipping", valid_time);
+                continue;
+            };
+
+            let to_f32 = |x: &f64| if x.abs() >= MISSING_VALUE { f32::NAN } else { *x as f32 };
+            let values = |name: &str| -> Vec<f32> {
+                match step_fields.iter().find(|f| f.short_name == name) {
+                    Some(field) if field.values.len() == grid.len() => field.values.iter().map(to_f32).collect(),
+                    _ => Vec::new(),
+                }
+            };
+
+            let swh_values = values("swh");
+            if swh_values.is_empty() {
+                log::warn!("Incomplete wave step at {}, skipping", valid_time);
+                continue;
+            }
+
+            snapshots.push(WaveSnapshot {
+                valid_time,
+                grid,
+                swh: swh_values,
+                mwd: values("mwd"),
+                mwp: values("mwp"),
+            });
+        }
+
+        snapshots
+    }
+
+    /// Loads a GRIB file and extracts U and V components for wind using eccodes,
+    /// one snapshot per forecast step
+    pub fn load_wind_data<P: AsRef<Path>>(&self, path: P) -> Result<WindField, Box<dyn std::error::Error>> {
+        let path = path.as_ref();
+        info!("Loading wind data from GRIB file: {:?}", path);
+
+        let fields = self.read_fields(path, &WIND_U_NAMES.iter().chain(WIND_V_NAMES).copied().collect::<Vec<_>>())?;
+
+        let mut wind_field = WindField::default();
+        for snapshot in Self::assemble_vector_steps(fields, WIND_U_NAMES, WIND_V_NAMES) {
+            wind_field.insert_snapshot(snapshot);
         }
         
-        info!("Successfully loaded {} wind points.", wind_data.len());
-        Ok(wind_data)
+        info!("Successfully loaded {} wind forecast steps.", wind_field.snapshots.len());
+        Ok(wind_field)
     }
 
-    /// Loads a GRIB file and extracts U and V components for ocean currents using eccodes
-    pub fn load_current_data<P: AsRef<Path>>(&self, path: P) -> Result<Vec<(Coordinate, CurrentData)>, Box<dyn std::error::Error>> {
+    /// Loads a GRIB file and extracts the surface U and V components for ocean currents
+    /// using eccodes, one snapshot per forecast step
+    pub fn load_current_data<P: AsRef<Path>>(&self, path: P) -> Result<CurrentField, Box<dyn std::error::Error>> {
         let path = path.as_ref();
         info!("Loading current data from GRIB file: {:?}", path);
-        
-        let mut current_data = Vec::new();
-        current_data.push((Coordinate::new(0.0, 0.0), CurrentData { u: 1.0, v: 0.5 }));
-        
-        Ok(current_data)
+
+        let fields = self.read_fields(path, &CURRENT_U_NAMES.iter().chain(CURRENT_V_NAMES).copied().collect::<Vec<_>>())?;
+
+        let mut current_field = CurrentField::default();
+        for snapshot in Self::assemble_vector_steps(fields, CURRENT_U_NAMES, CURRENT_V_NAMES) {
+            current_field.insert_snapshot(snapshot);
+        }
+
+        info!("Successfully loaded {} current forecast steps.", current_field.snapshots.len());
+        Ok(current_field)
+    }
+
+    /// Loads a GRIB file and extracts significant wave height, mean wave direction
+    /// and mean wave period using eccodes, one snapshot per forecast step
+    pub fn load_wave_data<P: AsRef<Path>>(&self, path: P) -> Result<WaveField, Box<dyn std::error::Error>> {
+        let path = path.as_ref();
+        info!("Loading wave data from GRIB file: {:?}", path);
+
+        let fields = self.read_fields(path, WAVE_NAMES)?;
+
+        let mut wave_field = WaveField::default();
+        for snapshot in Self::assemble_wave_steps(fields) {
+            wave_field.insert_snapshot(snapshot);
+        }
+
+        info!("Successfully loaded {} wave forecast steps.", wave_field.snapshots.len());
+        Ok(wave_field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_time() {
+        let base = GribLoader::valid_time(20240115, 1200, "0", "h").unwrap();
+        assert_eq!(base.format("%Y-%m-%d %H:%M").to_string(), "2024-01-15 12:00");
+
+        let step = GribLoader::valid_time(20240115, 1200, "18", "h").unwrap();
+        assert_eq!(step.format("%Y-%m-%d %H:%M").to_string(), "2024-01-16 06:00");
+
+        // Accumulation ranges use the end of the range
+        let range = GribLoader::valid_time(20240115, 600, "0-6", "h").unwrap();
+        assert_eq!(range.format("%Y-%m-%d %H:%M").to_string(), "2024-01-15 12:00");
+
+        // Sub-hourly products, from stepUnits or from a unit suffix on the step
+        let minutes = GribLoader::valid_time(20240115, 1200, "45", "m").unwrap();
+        assert_eq!(minutes.format("%Y-%m-%d %H:%M").to_string(), "2024-01-15 12:45");
+        let suffixed = GribLoader::valid_time(20240115, 1200, "0-15m", "h").unwrap();
+        assert_eq!(suffixed.format("%Y-%m-%d %H:%M").to_string(), "2024-01-15 12:15");
+        assert_eq!(GribLoader::valid_time(20240115, 0, "2", "6h"), GribLoader::valid_time(20240115, 1200, "0", "h"));
+        assert_eq!(GribLoader::step_units_name(0), "m");
+
+        assert!(GribLoader::valid_time(20241340, 0, "0", "h").is_none());
+        assert!(GribLoader::valid_time(20240115, 0, "1", "M").is_none());
+    }
+
+    #[test]
+    fn test_assemble_vector_steps_keeps_surface() {
+        let t0 = GribLoader::valid_time(20240115, 0, "0", "h").unwrap();
+        let t1 = GribLoader::valid_time(20240115, 0, "1", "h").unwrap();
+        let grid = GridGeometry { lat0: 50.0, lon0: -2.0, dlat: -1.0, dlon: 1.0, ni: 2, nj: 1 };
+        let field = |short_name: &str, valid_time, level, value: f64| GribField {
+            short_name: short_name.to_string(),
+            valid_time,
+            level,
+            values: vec![value, 9999.0],
+            grid: Some(grid),
+        };
+
+        let fields = vec![
+            field("uo", t0, 5, 0.3),
+            field("uo", t0, 0, 0.8),
+            field("vo", t0, 0, -0.2),
+            field("uo", t1, 0, 1.0), // No matching V component
+        ];
+
+        let snapshots = GribLoader::assemble_vector_steps(fields, CURRENT_U_NAMES, CURRENT_V_NAMES);
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(snapshots[0].valid_time, t0);
+        assert_eq!(snapshots[0].u[0], 0.8);
+        assert_eq!(snapshots[0].v[0], -0.2);
+        assert!(snapshots[0].u[1].is_nan() && snapshots[0].v[1].is_nan());
     }
 }
@@ -1,3 +1,6 @@
 pub mod grib;
 pub mod bufr;
+pub mod buoy;
+pub mod boat_log;
 pub mod polars;
+pub mod polar_formats;
@@ -0,0 +1,354 @@
+// Readers for the polar file formats we receive from designers, ORC and other routing tools
+
+use crate::parsers::polars::{PolarData, PolarError};
+
+/// ORC allowances are given in seconds per nautical mile
+const SECONDS_PER_HOUR: f32 = 3600.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolarFormat {
+    /// `TWA\TWS` header then one row per TWA. Covers our CSV, qtVlm/Adrena `.pol`
+    /// and OpenCPN weather routing polars, separated by `,`, `;`, tabs or spaces.
+    Table,
+    /// Expedition: one row per TWS, followed by `TWA BSP` pairs
+    Expedition,
+    /// ORC certificate JSON (`Allowances` with `WindSpeeds`, `WindAngles` and `R52`...)
+    OrcJson,
+    /// ORC certificate XML with the same `Allowances` fields as the JSON export
+    OrcXml,
+}
+
+impl PolarFormat {
+    /// Guesses the format from the file content
+    pub fn detect(content: &str) -> Option<Self> {
+        let trimmed = content.trim_start_matches('\u{feff}').trim_start();
+        if trimmed.starts_with('{') || trimmed.starts_with('[') {
+            return Some(PolarFormat::OrcJson);
+        }
+        if trimmed.starts_with('<') {
+            return Some(PolarFormat::OrcXml);
+        }
+
+        let (_, first) = data_lines(trimmed).next()?;
+        if first.to_lowercase().starts_with("twa") {
+            return Some(PolarFormat::Table);
+        }
+        let values = split_cells(first);
+        let numeric = values.iter().all(|v| parse_number(v).is_some());
+        (numeric && values.len() >= 3 && values.len() % 2 == 1).then_some(PolarFormat::Expedition)
+    }
+
+    pub fn parse(&self, content: &str) -> Result<PolarData, PolarError> {
+        let content = content.trim_start_matches('\u{feff}');
+        match self {
+            PolarFormat::Table => parse_table(content),
+            PolarFormat::Expedition => parse_expedition(content),
+            PolarFormat::OrcJson => parse_orc_json(content),
+            PolarFormat::OrcXml => parse_orc_xml(content),
+        }
+    }
+}
+
+/// Non-empty lines with their 1-based line number, without `!` / `#` comments
+fn data_lines(content: &str) -> impl Iterator<Item = (usize, &str)> {
+    content.lines()
+        .map(str::trim)
+        .enumerate()
+        .map(|(i, line)| (i + 1, line))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('!') && !line.starts_with('#'))
+}
+
+/// Splits a line on the first separator found among `;`, tab, `,` and whitespace
+fn split_cells(line: &str) -> Vec<String> {
+    let cells: Vec<&str> = if line.contains(';') {
+        line.split(';').collect()
+    } else if line.contains('\t') {
+        line.split('\t').collect()
+    } else if line.contains(',') {
+        return line.split(',').map(|c| c.trim().to_string()).collect();
+    } else {
+        line.split_whitespace().collect()
+    };
+    // With `;` or tab separators, a comma is a decimal separator (French Adrena exports)
+    cells.iter().map(|c| c.trim().replace(',', ".")).collect()
+}
+
+fn parse_number(cell: &str) -> Option<f32> {
+    cell.trim().trim_end_matches('°').parse().ok().filter(|v: &f32| v.is_finite())
+}
+
+/// Parses the non-empty cells of a line from `first_column` (1-based) as `(column, value)`,
+/// ignoring trailing separators
+fn parse_cells(line_no: usize, cells: &[String], first_column: usize) -> Result<Vec<(usize, f32)>, PolarError> {
+    cells.iter()
+        .enumerate()
+        .skip(first_column - 1)
+        .filter(|(_, cell)| !cell.is_empty())
+        .map(|(i, cell)| {
+            parse_number(cell)
+                .map(|v| (i + 1, v))
+                .ok_or_else(|| PolarError::InvalidValue { line: line_no, column: i + 1, value: cell.clone() })
+        })
+        .collect()
+}
+
+fn parse_table(content: &str) -> Result<PolarData, PolarError> {
+    let mut lines = data_lines(content);
+    let (header_line, header) = lines.next().ok_or(PolarError::Empty)?;
+    let tws_cells = parse_cells(header_line, &split_cells(header), 2)?;
+
+    let mut tws: Vec<f32> = Vec::with_capacity(tws_cells.len());
+    for (column, value) in tws_cells {
+        if tws.last().is_some_and(|&last| value <= last) {
+            return Err(PolarError::NonMonotonicAxis { axis: "TWS", line: header_line, column, value });
+        }
+        tws.push(value);
+    }
+
+    let mut twa: Vec<f32> = Vec::new();
+    let mut speeds = Vec::new();
+    for (line_no, line) in lines {
+        let values = parse_cells(line_no, &split_cells(line), 1)?;
+        let Some((&(column, angle), row)) = values.split_first() else { continue };
+
+        if row.len() != tws.len() {
+            return Err(PolarError::RaggedRow { line: line_no, expected: tws.len() + 1, found: values.len() });
+        }
+        if twa.last().is_some_and(|&last| angle <= last) {
+            return Err(PolarError::NonMonotonicAxis { axis: "TWA", line: line_no, column, value: angle });
+        }
+        if let Some(&(column, speed)) = row.iter().find(|(_, speed)| *speed < 0.0) {
+            return Err(PolarError::NegativeSpeed { line: line_no, column, speed });
+        }
+
+        twa.push(angle);
+        speeds.push(row.iter().map(|&(_, speed)| speed).collect());
+    }
+    if twa.is_empty() {
+        return Err(PolarError::Empty);
+    }
+
+    Ok(PolarData { tws, twa, speeds, ..Default::default() })
+}
+
+/// Linear interpolation in `(x, y)` points sorted by `x`, clamped at both ends
+fn interpolate_points(points: &[(f32, f32)], x: f32) -> f32 {
+    let Some(&(first_x, first_y)) = points.first() else { return 0.0 };
+    if x <= first_x {
+        return first_y;
+    }
+    for pair in points.windows(2) {
+        let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+        if x <= x1 {
+            let frac = if x1 > x0 { (x - x0) / (x1 - x0) } else { 0.0 };
+            return y0 + frac * (y1 - y0);
+        }
+    }
+    points.last().map(|&(_, y)| y).unwrap_or(0.0)
+}
+
+/// Each TWS row has its own angles: rows are resampled on the union of all angles
+fn parse_expedition(content: &str) -> Result<PolarData, PolarError> {
+    let mut rows: Vec<(f32, Vec<(f32, f32)>)> = Vec::new();
+    for (line_no, line) in data_lines(content) {
+        let values = parse_cells(line_no, &split_cells(line), 1)?;
+        let Some((&(column, tws), pairs)) = values.split_first() else { continue };
+
+        if pairs.len() % 2 != 0 {
+            return Err(PolarError::RaggedRow { line: line_no, expected: values.len() + 1, found: values.len() });
+        }
+        if rows.last().is_some_and(|&(last, _)| tws <= last) {
+            return Err(PolarError::NonMonotonicAxis { axis: "TWS", line: line_no, column, value: tws });
+        }
+
+        let mut points: Vec<(f32, f32)> = Vec::with_capacity(pairs.len() / 2);
+        for pair in pairs.chunks_exact(2) {
+            let ((angle_column, angle), (speed_column, speed)) = (pair[0], pair[1]);
+            if points.last().is_some_and(|&(last, _)| angle <= last) {
+                return Err(PolarError::NonMonotonicAxis { axis: "TWA", line: line_no, column: angle_column, value: angle });
+            }
+            if speed < 0.0 {
+                return Err(PolarError::NegativeSpeed { line: line_no, column: speed_column, speed });
+            }
+            points.push((angle, speed));
+        }
+        rows.push((tws, points));
+    }
+    if rows.is_empty() {
+        return Err(PolarError::Empty);
+    }
+
+    let mut twa: Vec<f32> = rows.iter().flat_map(|(_, points)| points.iter().map(|p| p.0)).collect();
+    twa.sort_by(f32::total_cmp);
+    twa.dedup();
+
+    let speeds = twa.iter()
+        .map(|&angle| rows.iter().map(|(_, points)| interpolate_points(points, angle)).collect())
+        .collect();
+
+    Ok(PolarData {
+        tws: rows.iter().map(|(tws, _)| *tws).collect(),
+        twa,
+        speeds,
+        ..Default::default()
+    })
+}
+
+/// Builds the speed table from ORC allowances: `angle_allowances` holds, for each
+/// wind angle, the allowance (s/NM) at every wind speed
+fn orc_polar(tws: Vec<f32>, twa: Vec<f32>, angle_allowances: Vec<Vec<f32>>) -> Result<PolarData, PolarError> {
+    for (name, axis) in [("WindSpeeds", &tws), ("WindAngles", &twa)] {
+        if let Some(pair) = axis.windows(2).find(|pair| pair[1] <= pair[0]) {
+            return Err(PolarError::Malformed(format!("{} not increasing at {}", name, pair[1])));
+        }
+    }
+
+    let speeds = angle_allowances.into_iter()
+        .zip(&twa)
+        .map(|(allowances, angle)| {
+            if allowances.len() != tws.len() {
+                return Err(PolarError::Malformed(format!("R{} has {} values for {} wind speeds", angle, allowances.len(), tws.len())));
+            }
+            Ok(allowances.iter().map(|&a| if a > 0.0 { SECONDS_PER_HOUR / a } else { 0.0 }).collect())
+        })
+        .collect::<Result<Vec<Vec<f32>>, _>>()?;
+
+    Ok(PolarData { tws, twa, speeds, ..Default::default() })
+}
+
+fn parse_orc_json(content: &str) -> Result<PolarData, PolarError> {
+    let root: serde_json::Value = serde_json::from_str(content)
+        .map_err(|e| PolarError::Malformed(e.to_string()))?;
+
+    // The ORC API nests certificates (`rms: [...]`), look for the allowances anywhere
+    fn find_allowances(value: &serde_json::Value) -> Option<&serde_json::Map<String, serde_json::Value>> {
+        match value {
+            serde_json::Value::Object(map) if map.contains_key("WindSpeeds") && map.contains_key("WindAngles") => Some(map),
+            serde_json::Value::Object(map) => map.values().find_map(find_allowances),
+            serde_json::Value::Array(items) => items.iter().find_map(find_allowances),
+            _ => None,
+        }
+    }
+    let allowances = find_allowances(&root)
+        .ok_or_else(|| PolarError::Malformed("no WindSpeeds/WindAngles allowances in ORC JSON".to_string()))?;
+
+    let numbers = |key: &str| -> Result<Vec<f32>, PolarError> {
+        allowances.get(key)
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| PolarError::Malformed(format!("missing {} in ORC JSON", key)))?
+            .iter()
+            .map(|v| v.as_f64().map(|f| f as f32).ok_or_else(|| PolarError::Malformed(format!("non-numeric value in {}", key))))
+            .collect()
+    };
+
+    let tws = numbers("WindSpeeds")?;
+    let twa = numbers("WindAngles")?;
+    let angle_allowances = twa.iter()
+        .map(|angle| numbers(&format!("R{}", angle)))
+        .collect::<Result<Vec<_>, _>>()?;
+    orc_polar(tws, twa, angle_allowances)
+}
+
+fn parse_orc_xml(content: &str) -> Result<PolarData, PolarError> {
+    let document = roxmltree::Document::parse(content)
+        .map_err(|e| PolarError::Malformed(e.to_string()))?;
+    let allowances = document.descendants()
+        .find(|n| n.has_tag_name("WindSpeeds"))
+        .and_then(|n| n.parent_element())
+        .ok_or_else(|| PolarError::Malformed("no WindSpeeds element in ORC XML".to_string()))?;
+
+    // Values are either listed in the element text or as one child element each
+    let numbers = |name: &str| -> Result<Vec<f32>, PolarError> {
+        let node = allowances.children()
+            .find(|n| n.has_tag_name(name))
+            .ok_or_else(|| PolarError::Malformed(format!("missing {} in ORC XML", name)))?;
+        let texts: Vec<&str> = if node.children().any(|c| c.is_element()) {
+            node.children().filter(|c| c.is_element()).filter_map(|c| c.text()).collect()
+        } else {
+            node.text().unwrap_or_default().split(|c: char| c == ',' || c.is_whitespace()).collect()
+        };
+        texts.iter()
+            .map(|t| t.trim())
+            .filter(|t| !t.is_empty())
+            .map(|t| t.parse::<f32>().map_err(|_| PolarError::Malformed(format!("non-numeric value '{}' in {}", t, name))))
+            .collect()
+    };
+
+    let tws = numbers("WindSpeeds")?;
+    let twa = numbers("WindAngles")?;
+    let angle_allowances = twa.iter()
+        .map(|angle| numbers(&format!("R{}", angle)))
+        .collect::<Result<Vec<_>, _>>()?;
+    orc_polar(tws, twa, angle_allowances)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_and_parse_tables() {
+        let qtvlm = "TWA\\TWS;6;10;20\n52;5,5;7,2;8,0\n90;6,5;8,1;9,4\n";
+        assert_eq!(PolarFormat::detect(qtvlm), Some(PolarFormat::Table));
+        let polar = PolarFormat::Table.parse(qtvlm).unwrap();
+        assert_eq!(polar.tws, vec![6.0, 10.0, 20.0]);
+        assert_eq!(polar.twa, vec![52.0, 90.0]);
+        assert_eq!(polar.speeds[1], vec![6.5, 8.1, 9.4]);
+
+        let opencpn = "TWA\\TWS\t6\t10\n60\t5.1\t7.0\n";
+        assert_eq!(PolarFormat::detect(opencpn), Some(PolarFormat::Table));
+        assert_eq!(PolarFormat::Table.parse(opencpn).unwrap().speeds, vec![vec![5.1, 7.0]]);
+
+        let csv = "twa/tws,5,10\n30,4.93,7.88\n";
+        assert_eq!(PolarFormat::detect(csv).unwrap().parse(csv).unwrap().speeds, vec![vec![4.93, 7.88]]);
+    }
+
+    #[test]
+    fn test_table_errors() {
+        let parse = |content: &str| PolarFormat::Table.parse(content).unwrap_err();
+
+        assert!(matches!(parse("twa/tws,5,10\n30,4.9,abc\n"), PolarError::InvalidValue { line: 2, column: 3, .. }));
+        assert!(matches!(parse("twa/tws,5,10\n30,4.9,7.8\n\n40,6.6\n"), PolarError::RaggedRow { line: 4, expected: 3, found: 2 }));
+        assert!(matches!(parse("twa/tws,5,10,8\n30,1,2,3\n"), PolarError::NonMonotonicAxis { axis: "TWS", line: 1, column: 4, .. }));
+        assert!(matches!(parse("twa/tws,5,10\n40,1,2\n30,1,2\n"), PolarError::NonMonotonicAxis { axis: "TWA", line: 3, column: 1, .. }));
+        assert!(matches!(parse("twa/tws,5,10\n30,4.9,-1\n"), PolarError::NegativeSpeed { line: 2, column: 3, .. }));
+        assert!(matches!(parse("twa/tws,5,x\n30,1,2\n"), PolarError::InvalidValue { line: 1, column: 3, .. }));
+        assert!(matches!(parse("twa/tws,5,10\n"), PolarError::Empty));
+        assert!(matches!(PolarFormat::Expedition.parse("6 45 4.0 90\n"), Err(PolarError::RaggedRow { line: 1, .. })));
+
+        let message = parse("twa/tws,5,10\n30,4.9,abc\n").to_string();
+        assert_eq!(message, "Invalid value 'abc' at line 2, column 3");
+    }
+
+    #[test]
+    fn test_parse_expedition() {
+        let content = "!Expedition polar\n6\t45\t4.0\t90\t6.0\t150\t5.0\n10\t40\t6.0\t90\t8.0\n";
+        assert_eq!(PolarFormat::detect(content), Some(PolarFormat::Expedition));
+
+        let polar = PolarFormat::Expedition.parse(content).unwrap();
+        assert_eq!(polar.tws, vec![6.0, 10.0]);
+        assert_eq!(polar.twa, vec![40.0, 45.0, 90.0, 150.0]);
+        // 10 kt row has no 45° point: interpolated between 40° and 90°
+        assert!((polar.speeds[1][1] - 6.2).abs() < 1e-4);
+        assert_eq!(polar.speeds[2], vec![6.0, 8.0]);
+    }
+
+    #[test]
+    fn test_parse_orc() {
+        let json = r#"{"rms": [{"SailNo": "FRA 1", "Allowances": {
+            "WindSpeeds": [6, 10], "WindAngles": [52, 90],
+            "R52": [720.0, 480.0], "R90": [600.0, 400.0]}}]}"#;
+        assert_eq!(PolarFormat::detect(json), Some(PolarFormat::OrcJson));
+        let polar = PolarFormat::OrcJson.parse(json).unwrap();
+        assert_eq!(polar.tws, vec![6.0, 10.0]);
+        assert_eq!(polar.speeds, vec![vec![5.0, 7.5], vec![6.0, 9.0]]);
+
+        let xml = "<ROOT><Allowances><WindSpeeds>6 10</WindSpeeds><WindAngles>52 90</WindAngles>\
+                   <R52><v>720</v><v>480</v></R52><R90>600,400</R90></Allowances></ROOT>";
+        assert_eq!(PolarFormat::detect(xml), Some(PolarFormat::OrcXml));
+        assert_eq!(PolarFormat::OrcXml.parse(xml).unwrap().speeds, polar.speeds);
+
+        let missing = r#"{"WindSpeeds": [6], "WindAngles": [52]}"#;
+        assert!(matches!(PolarFormat::OrcJson.parse(missing), Err(PolarError::Malformed(_))));
+    }
+}
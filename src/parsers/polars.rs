@@ -1,11 +1,11 @@
 // Placeholder for Sail Boat Polars loader
 
 use std::path::Path;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use log::info;
+use log::{error, info};
 use bevy::prelude::Resource;
 
+use crate::parsers::polar_formats::PolarFormat;
+
 #[derive(Debug, Clone, Resource, Default)]
 pub struct PolarData {
     /// True Wind Speeds (knots)
@@ -14,56 +14,179 @@ pub struct PolarData {
     pub twa: Vec<f32>,
     /// Boat speeds in knots: speeds[twa_idx][tws_idx]
     pub speeds: Vec<Vec<f32>>,
+    /// Sail names, when the polar is the envelope of a set of per-sail polars
+    pub sails: Vec<String>,
+    /// Per-sail boat speeds on the same axes: sail_speeds[sail_idx][twa_idx][tws_idx]
+    pub sail_speeds: Vec<Vec<Vec<f32>>>,
+}
+
+/// Error raised while loading a polar. Lines and columns are 1-based.
+#[derive(Debug)]
+pub enum PolarError {
+    Io(std::io::Error),
+    Empty,
+    UnknownFormat,
+    /// A cell that is not a finite number
+    InvalidValue { line: usize, column: usize, value: String },
+    /// A row whose number of values does not match the header
+    RaggedRow { line: usize, expected: usize, found: usize },
+    /// A TWS or TWA value not strictly greater than the previous one
+    NonMonotonicAxis { axis: &'static str, line: usize, column: usize, value: f32 },
+    NegativeSpeed { line: usize, column: usize, speed: f32 },
+    /// Structural error in a JSON/XML polar
+    Malformed(String),
+}
+
+impl std::fmt::Display for PolarError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PolarError::Io(e) => write!(f, "I/O error: {}", e),
+            PolarError::Empty => write!(f, "Empty polar file"),
+            PolarError::UnknownFormat => write!(f, "Unrecognised polar format"),
+            PolarError::InvalidValue { line, column, value } =>
+                write!(f, "Invalid value '{}' at line {}, column {}", value, line, column),
+            PolarError::RaggedRow { line, expected, found } =>
+                write!(f, "Line {} has {} values, expected {}", line, found, expected),
+            PolarError::NonMonotonicAxis { axis, line, column, value } =>
+                write!(f, "{} axis not increasing at line {}, column {} ({})", axis, line, column, value),
+            PolarError::NegativeSpeed { line, column, speed } =>
+                write!(f, "Negative boat speed {} at line {}, column {}", speed, line, column),
+            PolarError::Malformed(message) => write!(f, "Malformed polar: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for PolarError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PolarError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for PolarError {
+    fn from(e: std::io::Error) -> Self {
+        PolarError::Io(e)
+    }
 }
 
 impl PolarData {
-    pub fn load_from_csv<P: AsRef<Path>>(path: P) -> Self {
+    /// Loads our `twa/tws` comma-separated table
+    pub fn load_from_csv<P: AsRef<Path>>(path: P) -> Result<Self, PolarError> {
         info!("Loading polar data from CSV: {:?}", path.as_ref());
-        
-        let file = File::open(path).expect("Unable to open polar CSV");
-        let reader = BufReader::new(file);
-        
-        let mut lines = reader.lines();
-        
-        // First line is header: "twa/tws", "5", "10", ...
-        let header = lines.next().expect("Empty CSV").expect("Format error");
-        let parts: Vec<&str> = header.split(',').collect();
-        let tws_str: Vec<&str> = parts[1..].to_vec();
-        
-        let mut tws: Vec<f32> = Vec::new();
-        for val in tws_str {
-            tws.push(val.parse().unwrap_or(0.0));
-        }
-        
-        let mut twa_list = Vec::new();
-        let mut speeds = Vec::new();
+        let content = std::fs::read_to_string(path)?;
+        PolarFormat::Table.parse(&content)
+    }
 
-        for line_result in lines {
-            let line = line_result.expect("Error reading row");
-            if line.trim().is_empty() {
-                continue;
-            }
-            let row_parts: Vec<&str> = line.split(',').collect();
-            
-            let twa_val: f32 = row_parts[0].parse().unwrap_or(0.0);
-            twa_list.push(twa_val);
-            
-            let mut row_speeds = Vec::new();
-            for val in &row_parts[1..] {
-                row_speeds.push(val.parse().unwrap_or(0.0));
-            }
-            speeds.push(row_speeds);
+    /// Loads a polar file in any supported format, detected from its content
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, PolarError> {
+        info!("Loading polar data from: {:?}", path.as_ref());
+        let content = std::fs::read_to_string(path)?;
+        let format = PolarFormat::detect(&content).ok_or(PolarError::UnknownFormat)?;
+        info!("Detected polar format: {:?}", format);
+        format.parse(&content)
+    }
+
+    /// Loads every `*.csv` / `*.pol` polar of `dir` as one sail, named after the file stem
+    pub fn load_sail_set<P: AsRef<Path>>(dir: P) -> Result<Self, PolarError> {
+        info!("Loading sail polars from: {:?}", dir.as_ref());
+
+        let mut paths: Vec<_> = std::fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "csv" || ext == "pol"))
+            .collect();
+        paths.sort();
+        if paths.is_empty() {
+            return Err(PolarError::Empty);
         }
 
+        let sails = paths.iter()
+            .map(|path| {
+                let name = path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+                let polar = Self::load(path).inspect_err(|e| error!("Failed to load sail polar {:?}: {}", path, e))?;
+                Ok((name, polar))
+            })
+            .collect::<Result<Vec<_>, PolarError>>()?;
+        Ok(Self::from_sails(sails))
+    }
+
+    /// Builds the envelope of a set of per-sail polars. Every sail is resampled on the
+    /// union of the TWS/TWA axes; a sail is unusable (0 kt) outside its own TWA range.
+    pub fn from_sails(sails: Vec<(String, PolarData)>) -> Self {
+        let merge_axis = |axis: fn(&PolarData) -> &Vec<f32>| {
+            let mut values: Vec<f32> = sails.iter().flat_map(|(_, polar)| axis(polar).iter().copied()).collect();
+            values.sort_by(f32::total_cmp);
+            values.dedup();
+            values
+        };
+        let tws = merge_axis(|polar| &polar.tws);
+        let twa = merge_axis(|polar| &polar.twa);
+
+        let sail_speeds: Vec<Vec<Vec<f32>>> = sails.iter()
+            .map(|(_, polar)| {
+                let (min_twa, max_twa) = (polar.twa.first().copied().unwrap_or(0.0), polar.twa.last().copied().unwrap_or(0.0));
+                twa.iter()
+                    .map(|&a| tws.iter()
+                        .map(|&w| if a < min_twa || a > max_twa { 0.0 } else { polar.get_speed(w, a) })
+                        .collect())
+                    .collect()
+            })
+            .collect();
+
+        let speeds = (0..twa.len())
+            .map(|i| (0..tws.len())
+                .map(|j| sail_speeds.iter().map(|table| table[i][j]).fold(0.0, f32::max))
+                .collect())
+            .collect();
+
+        info!("Built polar envelope of {} sails", sails.len());
         Self {
             tws,
-            twa: twa_list,
+            twa,
             speeds,
+            sails: sails.into_iter().map(|(name, _)| name).collect(),
+            sail_speeds,
         }
     }
 
+    /// Fastest sail and its speed (knots) at the given TWS and TWA, `None` without a sail set
+    pub fn best_sail(&self, target_tws: f32, target_twa: f32) -> Option<(usize, f32)> {
+        self.sail_speeds.iter()
+            .map(|table| self.interpolate(table, target_tws, target_twa))
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    /// Speed (knots) of one sail of the set at the given TWS and TWA
+    pub fn sail_speed(&self, sail: usize, target_tws: f32, target_twa: f32) -> f32 {
+        self.sail_speeds.get(sail).map_or(0.0, |table| self.interpolate(table, target_tws, target_twa))
+    }
+
+    /// Fastest sail at every node of the polar grid: crossover[twa_idx][tws_idx]
+    pub fn crossover_chart(&self) -> Vec<Vec<Option<usize>>> {
+        (0..self.twa.len())
+            .map(|i| (0..self.tws.len())
+                .map(|j| self.sail_speeds.iter()
+                    .enumerate()
+                    .filter(|(_, table)| table[i][j] > 0.0)
+                    .max_by(|a, b| a.1[i][j].total_cmp(&b.1[i][j]))
+                    .map(|(sail, _)| sail))
+                .collect())
+            .collect()
+    }
+
     /// Bilinear interpolation to find the boat speed (in knots) for a given TWS and TWA.
+    /// With a sail set, this is the speed of the fastest sail.
     pub fn get_speed(&self, target_tws: f32, target_twa: f32) -> f32 {
+        match self.best_sail(target_tws, target_twa) {
+            Some((_, speed)) => speed,
+            None => self.interpolate(&self.speeds, target_tws, target_twa),
+        }
+    }
+
+    /// Bilinear interpolation in a `[twa_idx][tws_idx]` table on the polar axes
+    fn interpolate(&self, speeds: &[Vec<f32>], target_tws: f32, target_twa: f32) -> f32 {
         if self.tws.is_empty() || self.twa.is_empty() {
             return 0.0;
         }
@@ -102,10 +225,10 @@ impl PolarData {
         let twa0 = self.twa[twa_idx0];
         let twa1 = self.twa[twa_idx1];
 
-        let val00 = self.speeds[twa_idx0][tws_idx0];
-        let val01 = self.speeds[twa_idx0][tws_idx1];
-        let val10 = self.speeds[twa_idx1][tws_idx0];
-        let val11 = self.speeds[twa_idx1][tws_idx1];
+        let val00 = speeds[twa_idx0][tws_idx0];
+        let val01 = speeds[twa_idx0][tws_idx1];
+        let val10 = speeds[twa_idx1][tws_idx0];
+        let val11 = speeds[twa_idx1][tws_idx1];
 
         // 5. Bilinear Interpolation
         if tws_idx0 == tws_idx1 && twa_idx0 == twa_idx1 {
@@ -120,4 +243,210 @@ impl PolarData {
 
         val0 * (1.0 - twa_frac) + val1 * twa_frac // Interpolate across TWA
     }
+
+    /// Velocity made good towards (positive) or away from (negative) the wind, in knots
+    pub fn vmg(&self, tws: f32, twa: f32) -> f32 {
+        self.get_speed(tws, twa) * twa.to_radians().cos()
+    }
+
+    /// `(twa, vmg)` from 0° to 180° every `step` degrees
+    pub fn vmg_curve(&self, tws: f32, step: f32) -> Vec<(f32, f32)> {
+        angle_range(0.0, 180.0, step).map(|twa| (twa, self.vmg(tws, twa))).collect()
+    }
+
+    /// Searched angles, limited to the TWA range covered by the table
+    fn analytics_range(&self, from: f32, to: f32) -> impl Iterator<Item = f32> {
+        let min = self.twa.first().map_or(from, |&twa| twa.max(from));
+        let max = self.twa.last().map_or(to, |&twa| twa.min(to));
+        angle_range(min, max, ANALYTICS_STEP)
+    }
+
+    /// TWA and VMG of the best upwind angle
+    pub fn optimal_beat(&self, tws: f32) -> (f32, f32) {
+        self.analytics_range(0.0, 90.0)
+            .map(|twa| (twa, self.vmg(tws, twa)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap_or((0.0, 0.0))
+    }
+
+    /// TWA and downwind VMG (positive) of the best running angle
+    pub fn optimal_run(&self, tws: f32) -> (f32, f32) {
+        self.analytics_range(90.0, 180.0)
+            .map(|twa| (twa, -self.vmg(tws, twa)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap_or((180.0, 0.0))
+    }
+
+    /// TWA and boat speed of the fastest point of sail
+    pub fn max_speed(&self, tws: f32) -> (f32, f32) {
+        self.analytics_range(0.0, 180.0)
+            .map(|twa| (twa, self.get_speed(tws, twa)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap_or((0.0, 0.0))
+    }
+
+    /// Optimal beat/run angles and maximum speed for every TWS of the polar
+    pub fn optimal_angles(&self) -> Vec<PolarAngles> {
+        self.tws.iter()
+            .map(|&tws| {
+                let (beat_twa, beat_vmg) = self.optimal_beat(tws);
+                let (run_twa, run_vmg) = self.optimal_run(tws);
+                let (max_speed_twa, max_speed) = self.max_speed(tws);
+                PolarAngles { tws, beat_twa, beat_vmg, run_twa, run_vmg, max_speed_twa, max_speed }
+            })
+            .collect()
+    }
+
+    /// Resamples the polar every `tws_step` knots and `twa_step` degrees, with a
+    /// Catmull-Rom spline across TWA so curves stay smooth between table angles
+    pub fn resample(&self, tws_step: f32, twa_step: f32) -> Self {
+        let (Some(&tws_max), Some(&twa_min), Some(&twa_max)) = (self.tws.last(), self.twa.first(), self.twa.last()) else {
+            return self.clone();
+        };
+        let tws: Vec<f32> = angle_range(self.tws[0], tws_max, tws_step).collect();
+        let twa: Vec<f32> = angle_range(twa_min, twa_max, twa_step).collect();
+
+        let resample_table = |table: &[Vec<f32>]| -> Vec<Vec<f32>> {
+            twa.iter()
+                .map(|&a| tws.iter().map(|&w| self.smooth_interpolate(table, w, a)).collect())
+                .collect()
+        };
+
+        Self {
+            speeds: resample_table(&self.speeds),
+            sail_speeds: self.sail_speeds.iter().map(|table| resample_table(table)).collect(),
+            sails: self.sails.clone(),
+            tws,
+            twa,
+        }
+    }
+
+    /// Linear across TWS, Catmull-Rom across TWA (clamped to the table, never negative)
+    fn smooth_interpolate(&self, speeds: &[Vec<f32>], target_tws: f32, target_twa: f32) -> f32 {
+        let n = self.twa.len();
+        if n < 3 || self.tws.is_empty() {
+            return self.interpolate(speeds, target_tws, target_twa);
+        }
+        let twa = target_twa.clamp(self.twa[0], self.twa[n - 1]);
+        let i = self.twa.windows(2).position(|pair| twa <= pair[1]).unwrap_or(n - 2);
+        let (a0, a1) = (self.twa[i], self.twa[i + 1]);
+        let t = if a1 > a0 { (twa - a0) / (a1 - a0) } else { 0.0 };
+
+        // Speeds at the 4 surrounding angles, interpolated at the target TWS
+        let at = |k: usize| self.interpolate(speeds, target_tws, self.twa[k]);
+        let p0 = at(i.saturating_sub(1));
+        let p1 = at(i);
+        let p2 = at(i + 1);
+        let p3 = at((i + 2).min(n - 1));
+
+        let t2 = t * t;
+        let t3 = t2 * t;
+        let value = 0.5 * (2.0 * p1 + (p2 - p0) * t + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2 + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3);
+        value.max(0.0)
+    }
+}
+
+/// Angle resolution used for the optimal angle searches (degrees)
+const ANALYTICS_STEP: f32 = 0.5;
+
+/// `start..=end` every `step`, always ending exactly at `end`
+fn angle_range(start: f32, end: f32, step: f32) -> impl Iterator<Item = f32> {
+    let count = ((end - start) / step.max(1e-3)).ceil().max(0.0) as usize;
+    (0..=count).map(move |i| (start + i as f32 * step).min(end))
+}
+
+/// Optimal sailing angles for one wind speed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PolarAngles {
+    /// True wind speed (knots)
+    pub tws: f32,
+    /// Best upwind TWA (degrees) and VMG (knots)
+    pub beat_twa: f32,
+    pub beat_vmg: f32,
+    /// Best downwind TWA (degrees) and VMG (knots, positive)
+    pub run_twa: f32,
+    pub run_vmg: f32,
+    /// Fastest TWA (degrees) and its boat speed (knots)
+    pub max_speed_twa: f32,
+    pub max_speed: f32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sail(twa: Vec<f32>, speed: f32) -> PolarData {
+        PolarData {
+            tws: vec![10.0, 20.0],
+            speeds: vec![vec![speed, speed * 1.5]; twa.len()],
+            twa,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_vmg_analytics() {
+        let polar = PolarData::load_from_csv("data/imoca_60.csv").unwrap();
+
+        let (beat_twa, beat_vmg) = polar.optimal_beat(15.0);
+        let (run_twa, run_vmg) = polar.optimal_run(15.0);
+        assert!((35.0..=60.0).contains(&beat_twa), "Beat angle {}", beat_twa);
+        assert!((130.0..=170.0).contains(&run_twa), "Run angle {}", run_twa);
+        assert!(beat_vmg > 0.0 && run_vmg > beat_vmg);
+        assert!(beat_vmg >= polar.vmg(15.0, 60.0));
+
+        let (max_twa, max_speed) = polar.max_speed(15.0);
+        assert_eq!((max_twa, max_speed), (150.0, 19.41));
+
+        let angles = polar.optimal_angles();
+        assert_eq!(angles.len(), polar.tws.len());
+        assert_eq!(angles[2].beat_twa, beat_twa);
+
+        let curve = polar.vmg_curve(15.0, 10.0);
+        assert_eq!(curve.len(), 19);
+        assert_eq!(curve[18].0, 180.0);
+    }
+
+    #[test]
+    fn test_resample() {
+        let polar = PolarData::load_from_csv("data/imoca_60.csv").unwrap();
+        let fine = polar.resample(1.0, 1.0);
+
+        assert_eq!(fine.tws.len(), 31);
+        assert_eq!(fine.twa.len(), 151);
+        // Table nodes are kept exactly
+        let (i, j) = (fine.twa.iter().position(|&a| a == 90.0).unwrap(), fine.tws.iter().position(|&w| w == 15.0).unwrap());
+        assert!((fine.speeds[i][j] - 14.91).abs() < 1e-4);
+        // The spline passes through the nodes but is smoother than straight segments
+        let between = fine.get_speed(15.0, 95.0);
+        assert!(between > 14.91 && between < 16.19);
+    }
+
+    #[test]
+    fn test_sail_set_envelope() {
+        let polar = PolarData::from_sails(vec![
+            ("J1".to_string(), sail(vec![30.0, 90.0], 8.0)),
+            ("C0".to_string(), sail(vec![60.0, 120.0], 10.0)),
+            ("A2".to_string(), sail(vec![120.0, 180.0], 9.0)),
+        ]);
+
+        assert_eq!(polar.sails, vec!["J1", "C0", "A2"]);
+        assert_eq!(polar.twa, vec![30.0, 60.0, 90.0, 120.0, 180.0]);
+        assert_eq!(polar.tws, vec![10.0, 20.0]);
+
+        // Upwind only the J1 works, reaching the code zero wins, downwind the A2
+        assert_eq!(polar.best_sail(10.0, 30.0), Some((0, 8.0)));
+        assert_eq!(polar.best_sail(15.0, 90.0).map(|(s, _)| s), Some(1));
+        assert_eq!(polar.best_sail(10.0, 180.0), Some((2, 9.0)));
+        assert_eq!(polar.get_speed(20.0, 60.0), 15.0);
+
+        let chart = polar.crossover_chart();
+        let column: Vec<Option<usize>> = chart.iter().map(|row| row[0]).collect();
+        assert_eq!(column, vec![Some(0), Some(1), Some(1), Some(1), Some(2)]);
+
+        // A single table has no sail information
+        let single = sail(vec![0.0, 180.0], 5.0);
+        assert_eq!(single.best_sail(10.0, 90.0), None);
+        assert!(single.crossover_chart().iter().flatten().all(Option::is_none));
+    }
 }
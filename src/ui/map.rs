@@ -5,7 +5,7 @@ use futures_lite::future;
 use image::{load_from_memory_with_format, ImageFormat};
 use std::f64::consts::PI;
 
-use crate::engine::models::{Coordinate, WindField};
+use crate::engine::models::{Coordinate, ObservationSet, ObservationSource, WindField};
 
 
 // OpenStreetMap base tile URL format: https://tile.openstreetmap.org/{z}/{x}/{y}.png
@@ -284,11 +284,18 @@ pub fn render_grid_system(
 /// System to draw mathematical wind barbules using Bevy Gizmos over the Mercator projected grid
 pub fn render_wind_barbules_system(
     wind_field: Res<WindField>,
+    routing_state: Res<crate::engine::router::RoutingState>,
     mut gizmos: Gizmos,
     q_camera: Query<(&Camera, &Transform, &OrthographicProjection), With<Camera2d>>,
     q_window: Query<&Window, With<bevy::window::PrimaryWindow>>,
 ) {
     let zoom = 1;
+
+    // Display the forecast valid at the latest routing front
+    let display_time = routing_state.fronts.last()
+        .and_then(|front| front.first())
+        .map(|state| state.time)
+        .unwrap_or_else(chrono::Utc::now);
     
     // 1. Calculate visible Lat/Lon bounds
     let mut min_coord = Coordinate { lat: -80.0, lon: -180.0 };
@@ -333,7 +340,7 @@ pub fn render_wind_barbules_system(
         let mut lat = start_lat;
         while lat <= end_lat {
             let coord = Coordinate { lat, lon };
-            if let Some(wind) = wind_field.get_wind_at(&coord) {
+            if let Some(wind) = wind_field.wind_at(&coord, display_time) {
                 // Only draw points that have significant wind to avoid cluttering 0 values
                 let speed_kts = wind.speed() * ms_to_knots as f32;
                 if speed_kts < 2.0 { 
@@ -417,6 +424,35 @@ pub fn render_wind_barbules_system(
     }
 }
 
+/// Renders wind observations as a station circle with a stem towards where the wind
+/// comes from, colored by platform
+pub fn render_observations_system(
+    observations: Res<ObservationSet>,
+    mut gizmos: Gizmos,
+    q_camera: Query<&OrthographicProjection, With<Camera2d>>,
+) {
+    let zoom = 1;
+    let scale = q_camera.get_single().map(|p| p.scale).unwrap_or(1.0);
+    let stem_len = 1.0;
+
+    for obs in &observations.observations {
+        let color = match obs.source {
+            ObservationSource::Buoy => Color::srgba(1.0, 0.0, 1.0, 0.9), // Magenta
+            ObservationSource::Ship => Color::srgba(0.0, 1.0, 1.0, 0.9), // Cyan
+            ObservationSource::Scatterometer => Color::srgba(1.0, 1.0, 1.0, 0.6), // White
+            ObservationSource::Other => Color::srgba(0.6, 0.6, 0.6, 0.6), // Gray
+        };
+
+        let origin = project_mercator(&obs.position, zoom);
+        gizmos.circle_2d(origin, 0.4 * scale.min(1.0), color);
+
+        let wind_vec = Vec2::new(-obs.wind.u, -obs.wind.v).normalize_or_zero();
+        if wind_vec != Vec2::ZERO {
+            gizmos.line_2d(origin, origin + wind_vec * stem_len, color);
+        }
+    }
+}
+
 /// Renders the isochrone points from the routing state
 pub fn render_isochrones_system(
     routing_state: Res<crate::engine::router::RoutingState>,
@@ -434,6 +470,19 @@ pub fn render_isochrones_system(
     let start_px = project_mercator(&routing_state.router.start, zoom);
     gizmos.circle_2d(start_px, 1.0 * scale, Color::srgba(0.0, 1.0, 0.0, 1.0));
 
+    // Render the best route found so far (White)
+    let route = routing_state.reconstruct_route();
+    for leg in &route {
+        let from_px = project_mercator(&leg.from, zoom);
+        let to_px = project_mercator(&leg.to, zoom);
+        gizmos.line_2d(from_px, to_px, Color::srgba(1.0, 1.0, 1.0, 0.9));
+    }
+
+    // Highlight the leg inspected in the polar viewer (Yellow)
+    if let Some(leg) = routing_state.selected_leg.and_then(|idx| route.get(idx)) {
+        gizmos.circle_2d(project_mercator(&leg.from, zoom), 2.0 * scale, Color::srgba(1.0, 1.0, 0.0, 1.0));
+    }
+
     let last_idx = routing_state.fronts.len().saturating_sub(1);
 
     for (step_idx, front) in routing_state.fronts.iter().enumerate() {
@@ -459,3 +508,76 @@ pub fn render_isochrones_system(
         }
     }
 }
+
+/// Number of scanlines filling each isochrone band
+const BAND_ROWS: usize = 150;
+
+/// Scanline segments of every isochrone band, rebuilt only when the isochrones change
+#[derive(Default)]
+pub struct IsochroneBands {
+    generation: Option<u64>,
+    rows: Vec<Vec<(Vec2, Vec2)>>,
+}
+
+/// Horizontal segments inside the rings with the even-odd rule, so the inner rings
+/// of the previous isochrone are left empty
+fn scanline_fill(rings: &[Vec<Vec2>], outer: &[Vec<Vec2>]) -> Vec<(Vec2, Vec2)> {
+    let (min_y, max_y) = outer.iter().flatten().fold((f32::MAX, f32::MIN), |(min, max), p| (min.min(p.y), max.max(p.y)));
+    if min_y >= max_y {
+        return Vec::new();
+    }
+
+    let mut segments = Vec::new();
+    let row_height = (max_y - min_y) / BAND_ROWS as f32;
+    for row in 0..BAND_ROWS {
+        let y = min_y + (row as f32 + 0.5) * row_height;
+        let mut crossings: Vec<f32> = rings.iter()
+            .flat_map(|ring| ring.windows(2))
+            .filter(|edge| (edge[0].y <= y) != (edge[1].y <= y))
+            .map(|edge| edge[0].x + (y - edge[0].y) / (edge[1].y - edge[0].y) * (edge[1].x - edge[0].x))
+            .collect();
+        crossings.sort_by(f32::total_cmp);
+        for pair in crossings.chunks_exact(2) {
+            segments.push((Vec2::new(pair[0], y), Vec2::new(pair[1], y)));
+        }
+    }
+    segments
+}
+
+/// Fills the area gained at every step, between consecutive isochrones
+pub fn render_isochrone_bands_system(
+    routing_state: Res<crate::engine::router::RoutingState>,
+    mut gizmos: Gizmos,
+    mut bands: Local<IsochroneBands>,
+) {
+    if !routing_state.show_bands { return; }
+    let isochrones = routing_state.isochrones();
+
+    let generation = routing_state.isochrone_generation();
+    if bands.generation != Some(generation) {
+        let project_rings = |isochrone: &crate::engine::router::Isochrone| -> Vec<Vec<Vec2>> {
+            isochrone.area.0.iter()
+                .map(|poly| poly.exterior().coords().map(|c| project_mercator(&Coordinate::new(c.y, c.x), 1)).collect())
+                .collect()
+        };
+        let rings: Vec<Vec<Vec<Vec2>>> = isochrones.iter().map(project_rings).collect();
+        bands.rows = (0..rings.len())
+            .map(|k| {
+                let mut band = rings[k].clone();
+                if k > 0 {
+                    band.extend(rings[k - 1].iter().cloned());
+                }
+                scanline_fill(&band, &rings[k])
+            })
+            .collect();
+        bands.generation = Some(generation);
+    }
+
+    for (k, rows) in bands.rows.iter().enumerate() {
+        // Same hue as the dots of the front enclosed by this isochrone
+        let color = Color::hsla(((k + 1) as f32 * 20.0) % 360.0, 0.8, 0.5, 0.25);
+        for &(from, to) in rows {
+            gizmos.line_2d(from, to, color);
+        }
+    }
+}
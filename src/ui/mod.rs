@@ -7,20 +7,35 @@ use bevy::{
 };
 use bevy_egui::{egui, EguiContexts, EguiPlugin};
 
-use crate::engine::models::WindField;
+use crate::engine::models::{CurrentData, CurrentField, ObservationSet, RouteLeg, WaveField, WindField, WindInterpolation, WindObservation};
 use crate::engine::mask::LandMask;
-use crate::engine::router::RoutingState;
-use crate::engine::physics::PhysicsModel;
+use crate::engine::router::{Conditions, HeadingFan, PruningStrategy, RoutingState, SolveOutcome};
+use crate::engine::physics::{PhysicsModel, PolarEfficiency};
+use crate::parsers::bufr::BufrLoader;
+use crate::parsers::buoy::BuoyCsvLoader;
+use crate::parsers::boat_log::BoatLogLoader;
+use crate::engine::bias::BiasModel;
+use crate::engine::polar_learning::{LearnedPolar, PolarLearner};
 use crate::parsers::grib::GribLoader;
 use crate::parsers::polars::PolarData;
 
 pub mod map;
+pub mod polar_plot;
 use map::{render_openseamap_system, render_wind_barbules_system, TileManager};
 
 pub struct UiPlugin;
 
 #[derive(Component)]
-pub struct AsyncGribLoadTask(Task<Vec<(crate::engine::models::Coordinate, crate::engine::models::WindData)>>);
+pub struct AsyncGribLoadTask(Task<WindField>);
+
+#[derive(Component)]
+pub struct AsyncCurrentLoadTask(Task<CurrentField>);
+
+#[derive(Component)]
+pub struct AsyncWaveLoadTask(Task<WaveField>);
+
+#[derive(Component)]
+pub struct AsyncObservationLoadTask(Task<Vec<WindObservation>>);
 
 impl Plugin for UiPlugin {
     fn build(&self, app: &mut App) {
@@ -29,7 +44,12 @@ impl Plugin for UiPlugin {
         app.add_plugins(EguiPlugin)
             .init_resource::<TileManager>()
             .init_resource::<WindField>()
+            .init_resource::<CurrentField>()
+            .init_resource::<WaveField>()
+            .init_resource::<ObservationSet>()
+            .init_resource::<BiasModel>()
             .init_resource::<PolarData>()
+            .init_resource::<PhysicsModel>()
             .init_resource::<RoutingState>()
             .insert_resource(mask)
             .add_systems(Startup, (setup_camera, startup_load_grib))
@@ -37,12 +57,17 @@ impl Plugin for UiPlugin {
                 Update,
                 (
                     handle_grib_load_task,
+                    handle_current_load_task,
+                    handle_wave_load_task,
+                    handle_observation_load_task,
                     routing_step_system,
                     ui_panel_system,
                     render_openseamap_system,
                     map::render_grid_system,
+                    map::render_isochrone_bands_system,
                     map::render_isochrones_system,
                     render_wind_barbules_system,
+                    map::render_observations_system,
                 ),
             )
             .add_systems(Update, camera_movement_system);
@@ -66,11 +91,20 @@ fn setup_camera(mut commands: Commands) {
 }
 
 fn ui_panel_system(
+    mut commands: Commands,
     mut contexts: EguiContexts, 
-    wind_field: Res<WindField>,
+    mut wind_field: ResMut<WindField>,
+    current_field: Res<CurrentField>,
+    wave_field: Res<WaveField>,
+    observations: Res<ObservationSet>,
+    mut bias_model: ResMut<BiasModel>,
     mut polar_data: ResMut<PolarData>,
+    mut physics: ResMut<PhysicsModel>,
     mut routing_state: ResMut<RoutingState>,
     land_mask: Res<LandMask>,
+    mut polar_error: Local<Option<String>>,
+    mut learned_polar: Local<Option<(LearnedPolar, PolarData)>>,
+    mut reach_query: Local<(f64, f64)>,
 ) {
     egui::Window::new("AI Weather Routing Debugger")
         .default_size([400.0, 500.0])
@@ -78,10 +112,88 @@ fn ui_panel_system(
             ui.heading("Controls");
             
             if ui.button("Load Current GRIB").clicked() {
-                // Implementation for loading current data
+                spawn_current_load_task(&mut commands, "data/currents_sample.grib2");
             }
-            if ui.button("Load IMOCA 60 Polar").clicked() {
-                *polar_data = PolarData::load_from_csv("data/imoca_60.csv");
+            if ui.button("Load Wave GRIB").clicked() {
+                spawn_wave_load_task(&mut commands, "data/waves_sample.grib2");
+            }
+            if ui.button("Load BUFR Observations").clicked() {
+                spawn_bufr_load_task(&mut commands, "data/observations_sample.bufr");
+            }
+            if ui.button("Load CSV Buoy Reports").clicked() {
+                // Relative report times in the file count from the start of the forecast
+                let forecast_start = wind_field.time_range().map(|(start, _)| start);
+                spawn_buoy_csv_load_task(&mut commands, "data/buoy_reports.csv", forecast_start);
+            }
+            let polar_clicked = ui.button("Load IMOCA 60 Polar").clicked();
+            let sails_clicked = ui.button("Load IMOCA 60 Sail Set").clicked();
+            let loaded_polar = if polar_clicked {
+                Some(PolarData::load_from_csv("data/imoca_60.csv"))
+            } else if sails_clicked {
+                Some(PolarData::load_sail_set("data/sails"))
+            } else {
+                None
+            };
+            match loaded_polar {
+                Some(Ok(polar)) => {
+                    *polar_data = polar;
+                    *polar_error = None;
+                    *learned_polar = None;
+                }
+                Some(Err(e)) => {
+                    log::error!("Failed to load polar: {}", e);
+                    *polar_error = Some(e.to_string());
+                }
+                None => {}
+            }
+            if let Some(error) = polar_error.as_ref() {
+                ui.colored_label(egui::Color32::RED, format!("Polar not loaded: {}", error));
+            }
+
+            // Empirical polar from the boat's log, compared with the theoretical one it replaces
+            if ui.button("Learn Polar from Boat Log").clicked() {
+                match BoatLogLoader::new().load("data/boat_log.nmea") {
+                    Ok(samples) => {
+                        let theoretical = learned_polar.take().map_or_else(|| polar_data.clone(), |(_, theoretical)| theoretical);
+                        let learned = PolarLearner::for_polar(&theoretical).fit(&samples);
+                        *polar_data = learned.merged_with(&theoretical);
+                        *learned_polar = Some((learned, theoretical));
+                    }
+                    Err(e) => {
+                        log::error!("Failed to load boat log: {}", e);
+                        *polar_error = Some(e.to_string());
+                    }
+                }
+            }
+            if let Some((learned, theoretical)) = learned_polar.as_ref() {
+                let measured = learned.counts.iter().flatten().filter(|&&count| count >= learned.min_samples).count();
+                ui.label(format!("Learned from {} steady samples out of {}, {} cells measured", learned.steady_samples, learned.total_samples, measured));
+                if let Some(ratio) = learned.mean_performance(theoretical) {
+                    ui.label(format!("Measured speed: {:.0}% of the theoretical polar", ratio * 100.0));
+                }
+                egui::CollapsingHeader::new("Measured vs theoretical").show(ui, |ui| {
+                    egui::Grid::new("polar_performance_grid").striped(true).show(ui, |ui| {
+                        ui.label("TWA \\ TWS");
+                        for tws in &learned.polar.tws {
+                            ui.label(format!("{}", tws));
+                        }
+                        ui.end_row();
+                        for (i, ratios) in learned.performance(theoretical).iter().enumerate() {
+                            ui.label(format!("{}", learned.polar.twa[i]));
+                            for (j, ratio) in ratios.iter().enumerate() {
+                                match ratio {
+                                    Some(ratio) => ui.label(format!("{:.0}% ({})", ratio * 100.0, learned.counts[i][j])),
+                                    None => ui.label("-"),
+                                };
+                            }
+                            ui.end_row();
+                        }
+                    });
+                });
+                if ui.button("Revert to Theoretical Polar").clicked() {
+                    *polar_data = theoretical.clone();
+                    *learned_polar = None;
+                }
             }
             
             ui.separator();
@@ -89,16 +201,76 @@ fn ui_panel_system(
             if let Some((min_lat, max_lat, min_lon, max_lon)) = wind_field.get_bounds() {
                 ui.label(format!("Latitude range: {:.2}° to {:.2}°", min_lat, max_lat));
                 ui.label(format!("Longitude range: {:.2}° to {:.2}°", min_lon, max_lon));
-                let points_count: usize = wind_field.chunks.values().map(|v| v.len()).sum();
-                ui.label(format!("Total points: {}", points_count));
+                let points_count: usize = wind_field.snapshots.first().map(|s| s.point_count()).unwrap_or(0);
+                ui.label(format!("Points per step: {}", points_count));
+                if let Some((first, last)) = wind_field.time_range() {
+                    ui.label(format!("Forecast: {} to {} UTC ({} steps)", first.format("%Y-%m-%d %H:%M"), last.format("%Y-%m-%d %H:%M"), wind_field.snapshots.len()));
+                }
+                let mut speed_direction = wind_field.interpolation == WindInterpolation::SpeedDirection;
+                if ui.checkbox(&mut speed_direction, "Interpolate speed/direction").changed() {
+                    wind_field.interpolation = if speed_direction { WindInterpolation::SpeedDirection } else { WindInterpolation::Components };
+                }
             } else {
                 ui.label("Waiting for background load...");
             }
+            match current_field.time_range() {
+                Some((first, last)) => {
+                    ui.label(format!("Currents: {} to {} UTC ({} steps)", first.format("%Y-%m-%d %H:%M"), last.format("%Y-%m-%d %H:%M"), current_field.snapshots.len()));
+                }
+                None => { ui.label("Currents: none loaded"); }
+            }
+            match wave_field.time_range() {
+                Some((first, last)) => {
+                    ui.label(format!("Waves: {} to {} UTC ({} steps)", first.format("%Y-%m-%d %H:%M"), last.format("%Y-%m-%d %H:%M"), wave_field.snapshots.len()));
+                }
+                None => { ui.label("Waves: none loaded"); }
+            }
+
+            ui.separator();
+            ui.heading("Observations");
+            if observations.observations.is_empty() {
+                ui.label("No observations loaded.");
+            } else {
+                ui.label(format!("Observations: {}", observations.observations.len()));
+                match observations.mean_speed_error(&wind_field) {
+                    Some((error, count)) => {
+                        ui.label(format!("Observed - forecast speed: {:+.1} kt over {} obs", error * 1.94384, count));
+                    }
+                    None => { ui.label("No observation within the forecast coverage."); }
+                }
+                if wind_field.bias_corrected {
+                    ui.label(format!("Forecast corrected from {} regions", bias_model.regions.len()));
+                }
+                // Once corrected, recomputing measures the bias left in the corrected forecast
+                let label = if wind_field.bias_corrected { "Recompute Bias Correction" } else { "Apply Bias Correction" };
+                if ui.button(label).clicked() {
+                    let model = BiasModel::compute(&wind_field, &observations.observations, 5.0, 3);
+                    if model.regions.is_empty() {
+                        log::warn!("No 5° region has 3 observations within the forecast, bias correction not applied");
+                    } else {
+                        model.apply(&mut wind_field);
+                        *bias_model = model;
+                    }
+                }
+                for region in bias_model.regions.iter().take(5) {
+                    ui.label(format!(
+                        "{:.1}°, {:.1}°: speed x{:.2}, direction {:+.0}° ({} obs)",
+                        region.center.lat, region.center.lon, region.speed_factor, region.direction_offset, region.count
+                    ));
+                }
+            }
             
             ui.separator();
             ui.heading("Polar Viewer");
             if !polar_data.twa.is_empty() {
-                egui::ScrollArea::both().max_height(200.0).show(ui, |ui| {
+                // Overlay the wind the boat sailed in on the selected route leg
+                let route = routing_state.reconstruct_route();
+                let overlay = routing_state.selected_leg
+                    .and_then(|idx| route.get(idx))
+                    .map(|leg| (leg.tws * 1.94384, leg.twa));
+                polar_plot::polar_diagram(ui, &polar_data, overlay);
+
+                egui::CollapsingHeader::new("Speed table").show(ui, |ui| egui::ScrollArea::both().max_height(200.0).show(ui, |ui| {
                     egui::Grid::new("polar_grid").striped(true).show(ui, |ui| {
                         ui.label("TWA \\ TWS");
                         for tws in &polar_data.tws {
@@ -114,7 +286,44 @@ fn ui_panel_system(
                             ui.end_row();
                         }
                     });
+                }));
+                ui.label("Optimal angles");
+                egui::Grid::new("optimal_angles_grid").striped(true).show(ui, |ui| {
+                    for header in ["TWS", "Beat", "VMG", "Run", "VMG", "Max"] {
+                        ui.label(header);
+                    }
+                    ui.end_row();
+                    for angles in polar_data.optimal_angles() {
+                        ui.label(format!("{} kt", angles.tws));
+                        ui.label(format!("{:.0}°", angles.beat_twa));
+                        ui.label(format!("{:.1}", angles.beat_vmg));
+                        ui.label(format!("{:.0}°", angles.run_twa));
+                        ui.label(format!("{:.1}", angles.run_vmg));
+                        ui.label(format!("{:.1} @ {:.0}°", angles.max_speed, angles.max_speed_twa));
+                        ui.end_row();
+                    }
                 });
+
+                if !polar_data.sails.is_empty() {
+                    ui.label(format!("Sail crossovers ({})", polar_data.sails.join(", ")));
+                    egui::ScrollArea::both().id_salt("crossover_scroll").max_height(200.0).show(ui, |ui| {
+                        egui::Grid::new("crossover_grid").striped(true).show(ui, |ui| {
+                            ui.label("TWA \\ TWS");
+                            for tws in &polar_data.tws {
+                                ui.label(format!("{} kt", tws));
+                            }
+                            ui.end_row();
+
+                            for (twa, row) in polar_data.twa.iter().zip(polar_data.crossover_chart()) {
+                                ui.label(format!("{}°", twa));
+                                for sail in row {
+                                    ui.label(sail.map(|s| polar_data.sails[s].as_str()).unwrap_or("-"));
+                                }
+                                ui.end_row();
+                            }
+                        });
+                    });
+                }
             } else {
                 ui.label("No Polar Data Loaded.");
             }
@@ -140,21 +349,133 @@ fn ui_panel_system(
                 }
                 ui.label(format!("Active branch count: {}", front.len()));
             }
+            ui.checkbox(&mut routing_state.show_bands, "Fill isochrone bands");
+
+            // Reachability query, on the destination until another point is entered
+            if *reach_query == (0.0, 0.0) {
+                *reach_query = (routing_state.router.destination.lat, routing_state.router.destination.lon);
+            }
+            ui.horizontal(|ui| {
+                ui.label("Earliest at");
+                ui.add(egui::DragValue::new(&mut reach_query.0).speed(0.01).range(-90.0..=90.0).suffix("° lat"));
+                ui.add(egui::DragValue::new(&mut reach_query.1).speed(0.01).range(-180.0..=180.0).suffix("° lon"));
+            });
+            match routing_state.time_to_reach(&crate::engine::models::Coordinate::new(reach_query.0, reach_query.1)) {
+                Some(time) => { ui.label(format!("Reachable from {} UTC", time.format("%Y-%m-%d %H:%M"))); }
+                None => { ui.label("Not reached by the fronts yet"); }
+            }
+            let route = routing_state.reconstruct_route();
+            if let Some(leg) = route.last() {
+                ui.label(format!("Best leg: HDG {:.0}°, {}, SOG {:.1} kt", leg.heading, leg.annotation(), leg.sog * 1.94384));
+            }
+            if !route.is_empty() {
+                let mut inspect = routing_state.selected_leg.is_some();
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut inspect, "Inspect leg");
+                    let mut idx = routing_state.selected_leg.unwrap_or(route.len() - 1).min(route.len() - 1);
+                    ui.add_enabled(inspect, egui::Slider::new(&mut idx, 0..=route.len() - 1));
+                    routing_state.selected_leg = inspect.then_some(idx);
+                });
+                if let Some(leg) = routing_state.selected_leg.and_then(|idx| route.get(idx)) {
+                    ui.label(format!(
+                        "{} UTC: TWS {:.0} kt, {}, SOG {:.1} kt",
+                        leg.start_time.format("%d %H:%M"), leg.tws * 1.94384, leg.annotation(), leg.sog * 1.94384
+                    ));
+                }
+            }
+            for (time, sail) in RouteLeg::sail_changes(&route) {
+                if let Some(name) = polar_data.sails.get(sail) {
+                    ui.label(format!("{} UTC: hoist {}", time.format("%d %H:%M"), name));
+                }
+            }
+
+            match &routing_state.outcome {
+                Some(SolveOutcome::Arrived { arrival }) => {
+                    ui.label(format!("Arrived: {} ({:.1}h)", arrival.time.format("%Y-%m-%d %H:%M"), arrival.elapsed_time / 3600.0));
+                }
+                Some(outcome) => { ui.label(format!("Stopped: {:?}", outcome)); }
+                None => {}
+            }
             
+            let efficiency = &mut physics.efficiency;
+            ui.add(egui::Slider::new(&mut efficiency.global, 0.5..=1.1).text("Polar efficiency"));
+            ui.add(egui::Slider::new(&mut efficiency.night_factor, 0.5..=1.0).text("Night factor"));
+            ui.horizontal(|ui| {
+                if ui.button("Short-handed preset").clicked() {
+                    *efficiency = PolarEfficiency::short_handed();
+                }
+                if ui.button("Full polar").clicked() {
+                    *efficiency = PolarEfficiency::default();
+                }
+            });
+            for band in &efficiency.bands {
+                ui.label(format!("TWS {:.0}-{:.0} kt, TWA {:.0}-{:.0}°: {:.0}%", band.tws.0, band.tws.1, band.twa.0, band.twa.1, band.factor * 100.0));
+            }
+
+            ui.checkbox(&mut routing_state.router.vmg_mode, "VMG mode (skip headings beyond beat/run angles)");
+
+            let fan = &mut routing_state.router.fan;
+            ui.add(egui::Slider::new(&mut fan.count, 12..=1440).logarithmic(true).text("Headings per point"));
+            ui.add(egui::Slider::new(&mut fan.half_width, 30.0..=180.0).text("Fan half-width (°)"));
             ui.horizontal(|ui| {
-                if ui.button("Step Forward").clicked() {
-                    let current_front = routing_state.fronts.last().unwrap().clone();
-                    
-                    use crate::engine::models::{WindData, CurrentData};
-                    let next_front = routing_state.router.step(
-                        &current_front, 
-                        &PhysicsModel::new(), 
+                ui.checkbox(&mut fan.adaptive, "Adaptive fan");
+                if ui.button("Ocean").clicked() {
+                    *fan = HeadingFan::ocean();
+                }
+                if ui.button("Coastal").clicked() {
+                    *fan = HeadingFan::coastal();
+                }
+            });
+
+            let pruning = &mut routing_state.router.pruning;
+            egui::ComboBox::from_label("Front pruning")
+                .selected_text(match pruning {
+                    PruningStrategy::PolygonUnion => "Polygon union",
+                    PruningStrategy::BearingSectors { .. } => "Bearing sectors",
+                    PruningStrategy::GreatCircleSectors { .. } => "Great-circle sectors",
+                })
+                .show_ui(ui, |ui| {
+                    // Compare variants only, so a tuned width is kept and still shows as selected
+                    for (strategy, label) in [
+                        (PruningStrategy::PolygonUnion, "Polygon union"),
+                        (PruningStrategy::BearingSectors { width: 1.0 }, "Bearing sectors"),
+                        (PruningStrategy::GreatCircleSectors { width: 1852.0 }, "Great-circle sectors"),
+                    ] {
+                        let selected = std::mem::discriminant(pruning) == std::mem::discriminant(&strategy);
+                        if ui.selectable_label(selected, label).clicked() && !selected {
+                            *pruning = strategy;
+                        }
+                    }
+                });
+            match pruning {
+                PruningStrategy::BearingSectors { width } => {
+                    ui.add(egui::Slider::new(width, 0.1..=5.0).text("Sector width (°)"));
+                }
+                PruningStrategy::GreatCircleSectors { width } => {
+                    ui.add(egui::Slider::new(width, 100.0..=20_000.0).logarithmic(true).text("Sector width (m)"));
+                }
+                PruningStrategy::PolygonUnion => {}
+            }
+
+            let penalties = &mut routing_state.router.manoeuvre_penalties;
+            ui.add(egui::Slider::new(&mut penalties.tack, 0.0..=900.0).text("Tack penalty (s)"));
+            ui.add(egui::Slider::new(&mut penalties.gybe, 0.0..=900.0).text("Gybe penalty (s)"));
+            ui.add(egui::Slider::new(&mut penalties.sail_change, 0.0..=900.0).text("Sail change penalty (s)"));
+
+            ui.horizontal(|ui| {
+                if ui.button("Step Forward").clicked() && routing_state.outcome.is_none() {
+                    let state = &mut *routing_state;
+                    state.outcome = state.router.advance(
+                        &mut state.fronts,
+                        &physics, 
                         &polar_data,
                         &land_mask,
-                        |coord| wind_field.get_wind_at(coord).unwrap_or(WindData { u: 0.0, v: 0.0 }), 
-                        |_| CurrentData { u: 0.0, v: 0.0 }
+                        &Conditions::new(
+                            |coord, time| wind_field.wind_at(coord, time),
+                            |coord, time| current_field.current_at(coord, time).unwrap_or(CurrentData { u: 0.0, v: 0.0 }),
+                            |coord, time| wave_field.sea_state_at(coord, time)
+                        )
                     );
-                    routing_state.fronts.push(next_front);
                 }
                 
                 let play_label = if routing_state.is_playing { "Pause" } else { "Play" };
@@ -246,7 +567,10 @@ fn camera_movement_system(
 
 fn startup_load_grib(mut commands: Commands, mut polar_data: ResMut<PolarData>) {
     log::info!("Loading default IMOCA 60 polar...");
-    *polar_data = PolarData::load_from_csv("data/imoca_60.csv");
+    match PolarData::load_from_csv("data/imoca_60.csv") {
+        Ok(polar) => *polar_data = polar,
+        Err(e) => log::error!("Failed to load default polar: {}", e),
+    }
     
     log::info!("Spawning background task to load GRIB data...");
     let thread_pool = IoTaskPool::get();
@@ -256,27 +580,152 @@ fn startup_load_grib(mut commands: Commands, mut polar_data: ResMut<PolarData>)
             Ok(data) => data,
             Err(e) => {
                 log::error!("Failed to load GRIB in background: {}", e);
-                Vec::new()
+                WindField::default()
             }
         }
     });
     commands.spawn(AsyncGribLoadTask(task));
 }
 
+fn spawn_current_load_task(commands: &mut Commands, path: &'static str) {
+    log::info!("Spawning background task to load current GRIB data from {}...", path);
+    let thread_pool = IoTaskPool::get();
+    let task = thread_pool.spawn(async move {
+        let loader = GribLoader::new();
+        match loader.load_current_data(path) {
+            Ok(data) => data,
+            Err(e) => {
+                log::error!("Failed to load current GRIB in background: {}", e);
+                CurrentField::default()
+            }
+        }
+    });
+    commands.spawn(AsyncCurrentLoadTask(task));
+}
+
+fn spawn_wave_load_task(commands: &mut Commands, path: &'static str) {
+    log::info!("Spawning background task to load wave GRIB data from {}...", path);
+    let thread_pool = IoTaskPool::get();
+    let task = thread_pool.spawn(async move {
+        let loader = GribLoader::new();
+        match loader.load_wave_data(path) {
+            Ok(data) => data,
+            Err(e) => {
+                log::error!("Failed to load wave GRIB in background: {}", e);
+                WaveField::default()
+            }
+        }
+    });
+    commands.spawn(AsyncWaveLoadTask(task));
+}
+
+fn spawn_bufr_load_task(commands: &mut Commands, path: &'static str) {
+    log::info!("Spawning background task to load BUFR observations from {}...", path);
+    let thread_pool = IoTaskPool::get();
+    let task = thread_pool.spawn(async move {
+        match BufrLoader::new().load(path) {
+            Ok(data) => data,
+            Err(e) => {
+                log::error!("Failed to load BUFR in background: {}", e);
+                Vec::new()
+            }
+        }
+    });
+    commands.spawn(AsyncObservationLoadTask(task));
+}
+
+fn spawn_buoy_csv_load_task(commands: &mut Commands, path: &'static str, reference_time: Option<chrono::DateTime<chrono::Utc>>) {
+    log::info!("Spawning background task to load buoy reports from {}...", path);
+    let thread_pool = IoTaskPool::get();
+    let task = thread_pool.spawn(async move {
+        let loader = BuoyCsvLoader { reference_time };
+        match loader.load(path) {
+            Ok(data) => data,
+            Err(e) => {
+                log::error!("Failed to load buoy CSV in background: {}", e);
+                Vec::new()
+            }
+        }
+    });
+    commands.spawn(AsyncObservationLoadTask(task));
+}
+
+fn handle_observation_load_task(
+    mut commands: Commands,
+    mut tasks_query: Query<(Entity, &mut AsyncObservationLoadTask)>,
+    mut observations: ResMut<ObservationSet>,
+) {
+    for (entity, mut task) in &mut tasks_query {
+        if let Some(data) = futures_lite::future::block_on(futures_lite::future::poll_once(&mut task.0)) {
+            if !data.is_empty() {
+                observations.observations = data;
+                log::info!("Background observation loading complete. {} observations loaded.", observations.observations.len());
+            } else {
+                log::warn!("Background observation loading returned no observations. Keeping last known observations.");
+            }
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn handle_wave_load_task(
+    mut commands: Commands,
+    mut tasks_query: Query<(Entity, &mut AsyncWaveLoadTask)>,
+    mut wave_field: ResMut<WaveField>,
+) {
+    for (entity, mut task) in &mut tasks_query {
+        if let Some(data) = futures_lite::future::block_on(futures_lite::future::poll_once(&mut task.0)) {
+            if !data.snapshots.is_empty() {
+                *wave_field = data;
+                log::info!("Background wave loading complete. {} forecast steps loaded.", wave_field.snapshots.len());
+            } else {
+                log::warn!("Background wave loading returned empty data. Keeping last known valid wave data.");
+            }
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn handle_current_load_task(
+    mut commands: Commands,
+    mut tasks_query: Query<(Entity, &mut AsyncCurrentLoadTask)>,
+    mut current_field: ResMut<CurrentField>,
+) {
+    for (entity, mut task) in &mut tasks_query {
+        if let Some(data) = futures_lite::future::block_on(futures_lite::future::poll_once(&mut task.0)) {
+            if !data.snapshots.is_empty() {
+                *current_field = data;
+                log::info!("Background current loading complete. {} forecast steps loaded.", current_field.snapshots.len());
+            } else {
+                log::warn!("Background current loading returned empty data. Keeping last known valid current data.");
+            }
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
 fn handle_grib_load_task(
     mut commands: Commands,
     mut tasks_query: Query<(Entity, &mut AsyncGribLoadTask)>,
     mut wind_field: ResMut<WindField>,
+    mut routing_state: ResMut<RoutingState>,
 ) {
     for (entity, mut task) in &mut tasks_query {
         if let Some(data) = futures_lite::future::block_on(futures_lite::future::poll_once(&mut task.0)) {
-            if !data.is_empty() {
-                wind_field.chunks.clear();
-                let count = data.len();
-                for (coord, wind) in data {
-                    wind_field.insert_point(coord, wind);
+            if !data.snapshots.is_empty() {
+                *wind_field = data;
+                log::info!("Background GRIB loading complete. {} forecast steps loaded.", wind_field.snapshots.len());
+
+                // Move a routing that has not started yet into the forecast window
+                if let Some((first, last)) = wind_field.time_range() {
+                    if routing_state.fronts.len() == 1 {
+                        if let Some(start_state) = routing_state.fronts[0].first_mut() {
+                            if start_state.time < first || start_state.time > last {
+                                start_state.time = first;
+                            }
+                        }
+                    }
                 }
-                log::info!("Background GRIB loading complete. Chunked {} points into {} 1x1 degree cells.", count, wind_field.chunks.len());
             } else {
                 log::warn!("Background GRIB loading returned empty data. Keeping last known valid GRIB data.");
             }
@@ -290,23 +739,33 @@ fn routing_step_system(
     mut routing_state: ResMut<RoutingState>,
     land_mask: Res<LandMask>,
     wind_field: Res<WindField>,
+    current_field: Res<CurrentField>,
+    wave_field: Res<WaveField>,
     polar_data: Res<PolarData>,
+    physics: Res<PhysicsModel>,
 ) {
     if !routing_state.is_playing { return; }
+    if routing_state.outcome.is_some() {
+        routing_state.is_playing = false;
+        return;
+    }
     
     routing_state.step_timer.tick(time.delta());
     if routing_state.step_timer.just_finished() {
-        let current_front = routing_state.fronts.last().unwrap().clone();
-        
-        use crate::engine::models::{WindData, CurrentData};
-        let next_front = routing_state.router.step(
-            &current_front, 
-            &PhysicsModel::new(), 
+        let state = &mut *routing_state;
+        state.outcome = state.router.advance(
+            &mut state.fronts,
+            &physics, 
             &polar_data,
             &land_mask,
-            |coord| wind_field.get_wind_at(coord).unwrap_or(WindData { u: 0.0, v: 0.0 }), 
-            |_| CurrentData { u: 0.0, v: 0.0 }
+            &Conditions::new(
+                |coord, time| wind_field.wind_at(coord, time),
+                |coord, time| current_field.current_at(coord, time).unwrap_or(CurrentData { u: 0.0, v: 0.0 }),
+                |coord, time| wave_field.sea_state_at(coord, time)
+            )
         );
-        routing_state.fronts.push(next_front);
+        if let Some(outcome) = &state.outcome {
+            log::info!("Routing stopped: {:?}", outcome);
+        }
     }
 }
@@ -0,0 +1,103 @@
+use bevy_egui::egui::{self, Align2, Color32, FontId, Pos2, Sense, Shape, Stroke, Vec2};
+
+use crate::parsers::polars::PolarData;
+
+/// Radius of the half-circle in pixels
+const RADIUS: f32 = 150.0;
+/// Angle resolution of the plotted curves (degrees)
+const CURVE_STEP: f32 = 2.0;
+
+/// Colour of the curve for the `index`-th of `count` wind speeds, from blue (light air) to red
+fn curve_color(index: usize, count: usize) -> Color32 {
+    let t = if count > 1 { index as f32 / (count - 1) as f32 } else { 0.0 };
+    egui::ecolor::Hsva::new(0.66 * (1.0 - t), 0.8, 0.95, 1.0).into()
+}
+
+/// Screen position of a point of sail: TWA 0 at the top, 180 at the bottom, starboard on the right
+fn polar_point(center: Pos2, scale: f32, twa: f32, speed: f32) -> Pos2 {
+    let angle = twa.to_radians();
+    center + Vec2::new(angle.sin(), -angle.cos()) * speed * scale
+}
+
+/// Draws the polar as a half-circle diagram, one curve per TWS, with the optimal beat
+/// and run angles marked. `overlay` is the boat's `(tws, twa)` in knots and degrees.
+pub fn polar_diagram(ui: &mut egui::Ui, polar: &PolarData, overlay: Option<(f32, f32)>) {
+    let (response, painter) = ui.allocate_painter(Vec2::new(RADIUS + 60.0, 2.0 * RADIUS + 40.0), Sense::hover());
+    let rect = response.rect;
+    let center = Pos2::new(rect.left() + 20.0, rect.center().y);
+
+    let max_speed = polar.speeds.iter().flatten().copied().fold(0.0, f32::max).max(1.0);
+    let ring_step = if max_speed > 20.0 { 10.0 } else { 5.0 };
+    let max_ring = (max_speed / ring_step).ceil() * ring_step;
+    let scale = RADIUS / max_ring;
+
+    // Speed rings and TWA spokes
+    let grid = Stroke::new(0.5, Color32::from_gray(90));
+    let mut ring = ring_step;
+    while ring <= max_ring {
+        let points = (0..=90).map(|i| polar_point(center, scale, i as f32 * 2.0, ring)).collect();
+        painter.add(Shape::line(points, grid));
+        painter.text(polar_point(center, scale, 0.0, ring), Align2::LEFT_BOTTOM, format!("{}", ring), FontId::proportional(10.0), Color32::GRAY);
+        ring += ring_step;
+    }
+    for twa in (0..=180).step_by(30) {
+        let twa = twa as f32;
+        painter.line_segment([center, polar_point(center, scale, twa, max_ring)], grid);
+        painter.text(polar_point(center, scale, twa, max_ring + 12.0 / scale), Align2::CENTER_CENTER, format!("{}°", twa), FontId::proportional(10.0), Color32::GRAY);
+    }
+
+    let (Some(&twa_min), Some(&twa_max)) = (polar.twa.first(), polar.twa.last()) else { return };
+    let curve = |tws: f32| -> Vec<Pos2> {
+        let steps = ((twa_max - twa_min) / CURVE_STEP).ceil() as usize;
+        (0..=steps)
+            .map(|i| (twa_min + i as f32 * CURVE_STEP).min(twa_max))
+            .map(|twa| polar_point(center, scale, twa, polar.get_speed(tws, twa)))
+            .collect()
+    };
+
+    for (k, angles) in polar.optimal_angles().iter().enumerate() {
+        let color = curve_color(k, polar.tws.len());
+        painter.add(Shape::line(curve(angles.tws), Stroke::new(1.5, color)));
+
+        // Optimal VMG angles
+        for twa in [angles.beat_twa, angles.run_twa] {
+            painter.circle_filled(polar_point(center, scale, twa, polar.get_speed(angles.tws, twa)), 3.0, color);
+        }
+        painter.text(
+            Pos2::new(rect.right() - 5.0, rect.top() + 12.0 * k as f32),
+            Align2::RIGHT_TOP,
+            format!("{} kt", angles.tws),
+            FontId::proportional(10.0),
+            color,
+        );
+    }
+
+    if let Some((tws, twa)) = overlay {
+        painter.add(Shape::dashed_line(&curve(tws), Stroke::new(1.0, Color32::WHITE), 4.0, 3.0));
+        let boat = polar_point(center, scale, twa.abs(), polar.get_speed(tws, twa.abs()));
+        painter.circle_stroke(boat, 5.0, Stroke::new(2.0, Color32::YELLOW));
+        painter.text(boat + Vec2::new(8.0, 0.0), Align2::LEFT_CENTER, format!("Boat: TWS {:.0} kt, TWA {:.0}°", tws, twa.abs()), FontId::proportional(11.0), Color32::YELLOW);
+    }
+
+    // Hover readout on the curve closest to the pointer
+    if let Some(pointer) = response.hover_pos() {
+        let offset = pointer - center;
+        let twa = offset.x.atan2(-offset.y).to_degrees();
+        if (twa_min..=twa_max).contains(&twa) {
+            let pointer_speed = offset.length() / scale;
+            let closest = polar.tws.iter()
+                .map(|&tws| (tws, polar.get_speed(tws, twa)))
+                .min_by(|a, b| (a.1 - pointer_speed).abs().total_cmp(&(b.1 - pointer_speed).abs()));
+            if let Some((tws, speed)) = closest {
+                painter.circle_filled(polar_point(center, scale, twa, speed), 3.0, Color32::WHITE);
+                painter.text(
+                    Pos2::new(rect.left() + 20.0, rect.top()),
+                    Align2::LEFT_TOP,
+                    format!("TWA {:.0}°, BSP {:.2} kt (TWS {} kt)", twa, speed, tws),
+                    FontId::proportional(11.0),
+                    Color32::WHITE,
+                );
+            }
+        }
+    }
+}